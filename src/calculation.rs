@@ -1,3 +1,35 @@
+use crate::model::{
+    AdditionalLoan, Buyer, CashFlowFrequency, CashFlowItem, DayCount, ExpenseCategory,
+    ExpenseGrowth, GracePeriod, GracePeriodMode, House, Indexation, IntermediatePayments,
+    PaymentFrequency, Refinancing, Rental, RoundingMode, Simulation, TradeUp, WithdrawalRule,
+};
+
+/// Monthly income tax table (`carnê-leão`): upper limit of each bracket,
+/// its rate and the amount deducted from the tax.
+const INCOME_TAX_TABLE: [(f64, f64, f64); 5] = [
+    (2_259.20, 0.0, 0.0),
+    (2_826.65, 0.075, 169.44),
+    (3_751.05, 0.15, 381.44),
+    (4_664.68, 0.225, 662.77),
+    (f64::INFINITY, 0.275, 896.0),
+];
+
+/// Capital gains tax (`GCAP`) table: upper limit of each tranche of the gain
+/// and its rate.
+const CAPITAL_GAINS_TAX_TABLE: [(f64, f64); 4] = [
+    (5_000_000.0, 0.15),
+    (10_000_000.0, 0.175),
+    (30_000_000.0, 0.2),
+    (f64::INFINITY, 0.225),
+];
+
+/// Monthly reduction of the capital gain of homes bought after 2005
+/// (`fator de redução FR2`, Lei 11.196/2005).
+const CAPITAL_GAINS_MONTHLY_REDUCTION: f64 = 0.0035;
+
+/// Yield of the `FGTS`, 3% a year plus `TR`.
+const FGTS_MONTHLY_INTEREST: f64 = 0.0025;
+
 const ERR: f64 = 0.001;
 const MAX_ITERS: i32 = 10_000;
 const UPPER_BOUND: f64 = 5_000_000.0;
@@ -11,146 +43,2037 @@ pub(crate) enum AmortizationStrategyType {
 
 pub(crate) struct SimulationOutput {
     pub(crate) time_series: Vec<f64>,
+    /// Money on account plus the value of the house, minus what is owed.
+    pub(crate) net_worth: Vec<f64>,
+    /// Schedule of each of the houses bought, in the order they are bought,
+    /// followed by the additional loans.
+    pub(crate) loans: Vec<LoanSchedule>,
+    /// First month in which the money on account is negative, if any.
+    pub(crate) first_negative_month: Option<i32>,
+    /// Interest paid on negative balances (`cheque especial`) on each month.
+    pub(crate) monthly_credit_interest: Vec<f64>,
+    /// Installments plus expenses paid on each month.
+    pub(crate) monthly_outflows: Vec<f64>,
+    /// Signed value of each of the buyer's cash-flow items on each month.
+    pub(crate) cash_flow_breakdown: Vec<(String, Vec<f64>)>,
+    /// General expenses, which follow inflation, paid on each month.
+    pub(crate) general_expenses: Vec<f64>,
+    /// Expenses of each of the buyer's expense categories on each month.
+    pub(crate) expense_breakdown: Vec<(String, Vec<f64>)>,
+    /// Rent received on each month, after vacancy, fees and taxes.
+    pub(crate) rental_income: Vec<f64>,
+    /// `Carnê-leão` paid over the rent on each month.
+    pub(crate) rental_taxes: Vec<f64>,
+    /// Net rent of the first year of the rental over the price of the rented
+    /// houses.
+    pub(crate) rental_yield_on_cost: Option<f64>,
+    /// Money on the account of each buyer, when they keep separate accounts.
+    pub(crate) accounts: Vec<(String, Vec<f64>)>,
+    /// How the savings fund the expenses after each buyer who keeps an
+    /// account retires within the simulation.
+    pub(crate) retirements: Vec<RetirementReport>,
+}
+
+impl SimulationOutput {
+    pub(crate) fn total_credit_interest(&self) -> f64 {
+        self.monthly_credit_interest.iter().sum()
+    }
+
+    /// Gets the output in today's money, deflating the values of each month
+    /// by the inflation accumulated until then.
+    pub(crate) fn deflated(&self, inflation: f64) -> SimulationOutput {
+        let deflate = |series: &[f64]| deflate_from(series, 0, inflation);
+
+        SimulationOutput {
+            time_series: deflate(&self.time_series),
+            net_worth: deflate(&self.net_worth),
+            loans: self
+                .loans
+                .iter()
+                .map(|loan| loan.deflated(inflation))
+                .collect(),
+            first_negative_month: self.first_negative_month,
+            monthly_credit_interest: deflate(&self.monthly_credit_interest),
+            monthly_outflows: deflate(&self.monthly_outflows),
+            cash_flow_breakdown: self
+                .cash_flow_breakdown
+                .iter()
+                .map(|(name, series)| (name.clone(), deflate(series)))
+                .collect(),
+            general_expenses: deflate(&self.general_expenses),
+            expense_breakdown: self
+                .expense_breakdown
+                .iter()
+                .map(|(name, series)| (name.clone(), deflate(series)))
+                .collect(),
+            rental_income: deflate(&self.rental_income),
+            rental_taxes: deflate(&self.rental_taxes),
+            rental_yield_on_cost: self.rental_yield_on_cost,
+            accounts: self
+                .accounts
+                .iter()
+                .map(|(name, series)| (name.clone(), deflate(series)))
+                .collect(),
+            retirements: self
+                .retirements
+                .iter()
+                .map(|retirement| retirement.deflated(inflation))
+                .collect(),
+        }
+    }
+}
+
+/// Deflates a series whose first value happens after `offset` months.
+fn deflate_from(series: &[f64], offset: i32, inflation: f64) -> Vec<f64> {
+    series
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value / (1.0 + inflation).powi(i as i32 + offset))
+        .collect()
+}
+
+/// Withdrawals from the savings after the buyer retires.
+#[derive(Clone)]
+pub(crate) struct RetirementReport {
+    /// Name of the buyer who retires.
+    pub(crate) name: String,
+    /// Months of the simulation before the first pension.
+    pub(crate) month: i32,
+    /// Money on account on retirement.
+    pub(crate) savings: f64,
+    /// Taken from the savings on each month after retiring, to pay what the
+    /// pension and other incomes do not.
+    pub(crate) withdrawals: Vec<f64>,
+    /// Allowed by the withdrawal rule on each month after retiring, empty if
+    /// the rule takes whatever is needed.
+    pub(crate) allowed_withdrawals: Vec<f64>,
+    /// General expenses given up on each month after retiring to keep the
+    /// withdrawals within the rule.
+    pub(crate) expense_cuts: Vec<f64>,
+    /// First month after retiring in which the money on account is negative.
+    pub(crate) depleted_at: Option<i32>,
+    /// Month of the simulation in which the buyer reaches the target age.
+    pub(crate) target_month: i32,
+    /// Whether the savings last until the target age, unknown if the
+    /// simulation ends before it.
+    pub(crate) lasts_until_target: Option<bool>,
+}
+
+impl RetirementReport {
+    /// Months in which more is withdrawn than the withdrawal rule allows,
+    /// as the installments and the other expenses cannot be cut.
+    pub(crate) fn months_over_rule(&self) -> usize {
+        self.withdrawals
+            .iter()
+            .zip(&self.allowed_withdrawals)
+            .filter(|(withdrawal, allowed)| *withdrawal > *allowed)
+            .count()
+    }
+
+    fn deflated(&self, inflation: f64) -> RetirementReport {
+        RetirementReport {
+            savings: self.savings / (1.0 + inflation).powi(self.month),
+            withdrawals: deflate_from(&self.withdrawals, self.month, inflation),
+            allowed_withdrawals: deflate_from(&self.allowed_withdrawals, self.month, inflation),
+            expense_cuts: deflate_from(&self.expense_cuts, self.month, inflation),
+            ..self.clone()
+        }
+    }
+}
+
+/// Emergency reserve analysis over the money on account.
+pub(crate) struct ReserveReport {
+    /// Minimum money on account required on each month.
+    pub(crate) reserve_line: Vec<f64>,
+    /// How many months of the current outflow the money on account covers.
+    pub(crate) runway: Vec<f64>,
+    /// Months in which the money on account is below the reserve.
+    pub(crate) violations: Vec<i32>,
+    /// Greatest difference between the reserve and the money on account.
+    pub(crate) worst_shortfall: f64,
+}
+
+/// Installments of the house financing, independent of the buyer's money.
+#[derive(Default)]
+pub(crate) struct LoanSchedule {
+    pub(crate) name: String,
+    /// Months before the house is bought, with its down payment paid on the
+    /// last of them.
+    pub(crate) purchased_after: i32,
+    pub(crate) down_payment: f64,
+    /// Fees paid to the bank together with the down payment.
+    pub(crate) upfront_fees: f64,
+    /// Cash lent to the buyer on the last month before `purchased_after`, for
+    /// loans which do not finance a purchase.
+    pub(crate) disbursement: f64,
+    /// Regular installment paid on each month, after `starts_after` months.
     pub(crate) monthly_payments: Vec<f64>,
+    /// Extra amortization paid on each month, on top of the installment.
+    pub(crate) extra_amortizations: Vec<f64>,
+    /// Contractual intermediate payments (`parcelas intermediárias`) and
+    /// final balloon paid on each month, on top of the installment.
+    pub(crate) balloon_payments: Vec<f64>,
+    /// Insurance and administration fees charged with each installment,
+    /// mortgage insurance (`PMI`) included.
+    pub(crate) charges: Vec<f64>,
+    /// Property tax and homeowner's insurance escrowed with each installment.
+    pub(crate) escrow: Vec<f64>,
+    /// Month of the simulation of the first installment without `PMI`, once
+    /// the balance is low enough against the home's value.
+    pub(crate) pmi_drops_at: Option<i32>,
+    /// Outstanding balance before each installment, followed by the balance
+    /// after the last one.
+    pub(crate) balances: Vec<f64>,
+    /// Months before the amortization starts, e.g. during construction.
+    pub(crate) starts_after: i32,
     pub(crate) ends_after: i32,
+    /// Payments to the builder on each month of the construction, with
+    /// intermediate payments (`balões`) included.
+    pub(crate) construction_payments: Vec<f64>,
+    /// Interest over the amount released by the bank (`juros de obra`) on
+    /// each month of the construction.
+    pub(crate) construction_interest: Vec<f64>,
+    pub(crate) refinancing: Option<RefinancingReport>,
+    /// Resets of an adjustable rate, in the order they happen.
+    pub(crate) rate_resets: Vec<RateReset>,
+    pub(crate) sale: Option<SaleReport>,
 }
 
-/// Gets the monthly timeseries of money on account after buying house.
+/// New rate of an adjustable-rate loan and the installment recomputed with it.
+#[derive(Clone)]
+pub(crate) struct RateReset {
+    /// Month of the simulation of the first installment at the new rate.
+    pub(crate) month: i32,
+    pub(crate) monthly_interest: f64,
+    pub(crate) first_payment: f64,
+}
+
+/// Outcome of selling the house.
+#[derive(Clone)]
+pub(crate) struct SaleReport {
+    /// Month of the simulation in which the house is sold.
+    pub(crate) month: i32,
+    pub(crate) sale_price: f64,
+    pub(crate) broker_commission: f64,
+    /// Balance of the financing paid off with the proceeds.
+    pub(crate) outstanding_balance: f64,
+    pub(crate) capital_gain: f64,
+    pub(crate) capital_gains_tax: f64,
+}
+
+impl SaleReport {
+    /// Gets what is left for the buyer after the commission, the financing
+    /// and the taxes are paid.
+    pub(crate) fn net_proceeds(&self) -> f64 {
+        self.sale_price - self.broker_commission - self.outstanding_balance - self.capital_gains_tax
+    }
+
+    fn deflated(&self, inflation: f64) -> SaleReport {
+        let factor = (1.0 + inflation).powi(self.month - 1);
+
+        SaleReport {
+            month: self.month,
+            sale_price: self.sale_price / factor,
+            broker_commission: self.broker_commission / factor,
+            outstanding_balance: self.outstanding_balance / factor,
+            capital_gain: self.capital_gain / factor,
+            capital_gains_tax: self.capital_gains_tax / factor,
+        }
+    }
+}
+
+/// Comparison between refinancing the loan and keeping the original one.
+#[derive(Clone)]
+pub(crate) struct RefinancingReport {
+    /// Month of the simulation in which the new loan starts.
+    pub(crate) month: i32,
+    pub(crate) switching_costs: f64,
+    /// Installments the original loan would still have from `month` on.
+    pub(crate) original_payments: Vec<f64>,
+    /// Installments plus extra amortizations the original loan would still
+    /// have from `month` on.
+    pub(crate) original_outflows: Vec<f64>,
+    /// Installments plus extra amortizations of the new loan.
+    pub(crate) new_outflows: Vec<f64>,
+    pub(crate) new_first_payment: f64,
+}
+
+impl RefinancingReport {
+    /// Gets how much less is paid with the new loan, switching costs included.
+    pub(crate) fn net_savings(&self) -> f64 {
+        self.original_outflows.iter().sum::<f64>()
+            - self.new_outflows.iter().sum::<f64>()
+            - self.switching_costs
+    }
+
+    fn deflated(&self, inflation: f64) -> RefinancingReport {
+        let offset = self.month - 1;
+        let factor = |i: usize| (1.0 + inflation).powi(i as i32 + offset);
+        let deflate = |series: &[f64]| -> Vec<f64> {
+            series
+                .iter()
+                .enumerate()
+                .map(|(i, value)| value / factor(i))
+                .collect()
+        };
+
+        RefinancingReport {
+            month: self.month,
+            switching_costs: self.switching_costs / factor(0),
+            original_payments: deflate(&self.original_payments),
+            original_outflows: deflate(&self.original_outflows),
+            new_outflows: deflate(&self.new_outflows),
+            new_first_payment: self.new_first_payment / factor(0),
+        }
+    }
+}
+
+impl LoanSchedule {
+    fn deflated(&self, inflation: f64) -> LoanSchedule {
+        let deflate = |series: &[f64]| deflate_from(series, 0, inflation);
+        let deflate_loan = |series: &[f64]| deflate_from(series, self.starts_after, inflation);
+
+        LoanSchedule {
+            name: self.name.clone(),
+            purchased_after: self.purchased_after,
+            down_payment: self.down_payment
+                / (1.0 + inflation).powi((self.purchased_after - 1).max(0)),
+            upfront_fees: self.upfront_fees
+                / (1.0 + inflation).powi((self.purchased_after - 1).max(0)),
+            disbursement: self.disbursement
+                / (1.0 + inflation).powi((self.purchased_after - 1).max(0)),
+            monthly_payments: deflate_loan(&self.monthly_payments),
+            extra_amortizations: deflate_loan(&self.extra_amortizations),
+            balloon_payments: deflate_loan(&self.balloon_payments),
+            charges: deflate_loan(&self.charges),
+            escrow: deflate_loan(&self.escrow),
+            pmi_drops_at: self.pmi_drops_at,
+            balances: deflate_loan(&self.balances),
+            starts_after: self.starts_after,
+            ends_after: self.ends_after,
+            construction_payments: deflate(&self.construction_payments),
+            construction_interest: deflate(&self.construction_interest),
+            refinancing: self
+                .refinancing
+                .as_ref()
+                .map(|refinancing| refinancing.deflated(inflation)),
+            rate_resets: self
+                .rate_resets
+                .iter()
+                .map(|reset| RateReset {
+                    first_payment: reset.first_payment / (1.0 + inflation).powi(reset.month - 1),
+                    ..reset.clone()
+                })
+                .collect(),
+            sale: self.sale.as_ref().map(|sale| sale.deflated(inflation)),
+        }
+    }
+
+    /// Whether the house is owned after the given number of months.
+    pub(crate) fn owns_house_at(&self, months_elapsed: usize) -> bool {
+        months_elapsed >= self.purchased_after as usize
+            && self
+                .sale
+                .as_ref()
+                .map_or(true, |sale| months_elapsed < sale.month as usize)
+    }
+
+    /// Gets the down payment and the upfront fees paid on the given month, if
+    /// the house is bought after the start of the simulation.
+    pub(crate) fn down_payment_at(&self, month_index: usize) -> f64 {
+        if self.purchased_after > 0 && month_index + 1 == self.purchased_after as usize {
+            self.down_payment + self.upfront_fees
+        } else {
+            0.0
+        }
+    }
+
+    /// Gets the cash lent to the buyer on the given month.
+    pub(crate) fn disbursement_at(&self, month_index: usize) -> f64 {
+        if month_index + 1 == self.purchased_after as usize {
+            self.disbursement
+        } else {
+            0.0
+        }
+    }
+
+    /// Gets the installment paid on the given month, counting from the start
+    /// of the simulation.
+    pub(crate) fn payment_at(&self, month_index: usize) -> f64 {
+        month_index
+            .checked_sub(self.starts_after as usize)
+            .and_then(|i| self.monthly_payments.get(i))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Gets the extra amortization paid on the given month, counting from
+    /// the start of the simulation.
+    pub(crate) fn extra_amortization_at(&self, month_index: usize) -> f64 {
+        month_index
+            .checked_sub(self.starts_after as usize)
+            .and_then(|i| self.extra_amortizations.get(i))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Gets the insurance and fees charged on the given month, counting from
+    /// the start of the simulation.
+    pub(crate) fn charges_at(&self, month_index: usize) -> f64 {
+        month_index
+            .checked_sub(self.starts_after as usize)
+            .and_then(|i| self.charges.get(i))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Gets the property tax and insurance escrowed on the given month,
+    /// counting from the start of the simulation.
+    pub(crate) fn escrow_at(&self, month_index: usize) -> f64 {
+        month_index
+            .checked_sub(self.starts_after as usize)
+            .and_then(|i| self.escrow.get(i))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Gets everything paid to the bank, from the upfront fees to the
+    /// payoff, without the down payment.
+    pub(crate) fn total_paid(&self) -> f64 {
+        let paid: f64 = [
+            &self.monthly_payments,
+            &self.extra_amortizations,
+            &self.balloon_payments,
+            &self.charges,
+        ]
+        .iter()
+        .map(|series| series.iter().sum::<f64>())
+        .sum();
+
+        let payoff = self
+            .sale
+            .as_ref()
+            .map_or(0.0, |sale| sale.outstanding_balance);
+
+        self.upfront_fees + paid + payoff
+    }
+
+    /// Gets the effective cost of the loan (`CET`) as a yearly rate: the rate
+    /// at which everything paid to the bank is worth the value financed.
+    pub(crate) fn effective_cost(&self) -> Option<f64> {
+        let financed = self.balances.first().copied().unwrap_or(0.0) - self.upfront_fees;
+
+        let mut flows: Vec<f64> = (0..self.monthly_payments.len())
+            .map(|i| {
+                let at = |series: &[f64]| series.get(i).copied().unwrap_or(0.0);
+
+                at(&self.monthly_payments)
+                    + at(&self.extra_amortizations)
+                    + at(&self.balloon_payments)
+                    + at(&self.charges)
+            })
+            .collect();
+
+        if let (Some(sale), Some(last)) = (&self.sale, flows.last_mut()) {
+            *last += sale.outstanding_balance;
+        }
+
+        if financed <= 0.0 || flows.iter().sum::<f64>() < financed {
+            return None;
+        }
+
+        let present_value = |rate: f64| -> f64 {
+            flows
+                .iter()
+                .enumerate()
+                .map(|(i, flow)| flow / (1.0 + rate).powi(i as i32 + 1))
+                .sum()
+        };
+
+        let mut a = 0.0;
+        let mut b = 1.0;
+
+        for _ in 0..MAX_ITERS {
+            let c = (a + b) / 2.0;
+            let difference = present_value(c) - financed;
+
+            if difference.abs() < ERR {
+                return Some((1.0 + c).powi(12) - 1.0);
+            }
+
+            if difference > 0.0 {
+                a = c;
+            } else {
+                b = c;
+            }
+        }
+
+        Some((1.0 + (a + b) / 2.0).powi(12) - 1.0)
+    }
+
+    /// Gets the contractual intermediate payment made on the given month,
+    /// counting from the start of the simulation.
+    pub(crate) fn balloon_payment_at(&self, month_index: usize) -> f64 {
+        month_index
+            .checked_sub(self.starts_after as usize)
+            .and_then(|i| self.balloon_payments.get(i))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Gets the outstanding balance after the given number of months,
+    /// counting from the start of the simulation.
+    ///
+    /// During construction, the whole financed value is considered owed.
+    pub(crate) fn balance_at(&self, months_elapsed: usize) -> f64 {
+        if !self.owns_house_at(months_elapsed) {
+            return 0.0;
+        }
+
+        let installments_paid = months_elapsed.saturating_sub(self.starts_after as usize);
+
+        self.balances
+            .get(installments_paid)
+            .or(self.balances.last())
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Gets the net proceeds of selling the house on the given month.
+    pub(crate) fn sale_proceeds_at(&self, month_index: usize) -> f64 {
+        match &self.sale {
+            Some(sale) if sale.month as usize == month_index + 1 => sale.net_proceeds(),
+            _ => 0.0,
+        }
+    }
+
+    /// Gets the switching costs of a refinancing paid on the given month.
+    pub(crate) fn refinancing_costs_at(&self, month_index: usize) -> f64 {
+        match &self.refinancing {
+            Some(refinancing) if refinancing.month as usize == month_index + 1 => {
+                refinancing.switching_costs
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Gets everything paid for the construction on the given month.
+    pub(crate) fn construction_payment_at(&self, month_index: usize) -> f64 {
+        self.construction_payments
+            .get(month_index)
+            .copied()
+            .unwrap_or(0.0)
+            + self
+                .construction_interest
+                .get(month_index)
+                .copied()
+                .unwrap_or(0.0)
+    }
+}
+
+/// Gets the installments of the house financing with the given strategy, for
+/// a house bought after `purchased_after` months.
+pub(crate) fn calculate_loan_schedule(
+    house: &House,
+    strategy: AmortizationStrategyType,
+    purchased_after: i32,
+) -> LoanSchedule {
+    let construction = calculate_construction_schedule(house);
+    let starts_after = purchased_after + construction.months_until_delivery;
+
+    // The schedule is computed per installment, and only then gathered into
+    // the months of the simulation.
+    let periods_per_year = house.payment_frequency.periods_per_year();
+    let to_periods = |months: i32| months * periods_per_year / 12;
+    let period_interest = convert_monthly_interest(
+        house.house_monthly_interest,
+        house.payment_frequency,
+        house.day_count,
+    );
+    let grace_period = GracePeriod {
+        months: to_periods(house.grace_period.months),
+        ..house.grace_period
+    };
+
+    let mut schedule = calculate_amortization_after_grace(
+        construction.financed_value,
+        period_interest,
+        to_periods(house.months_to_pay).max(1),
+        house.yearly_extra_amortization,
+        strategy,
+        &grace_period,
+        &spread_over_periods(
+            &calculate_balloon_schedule(&house.intermediate_payments, house.months_to_pay),
+            periods_per_year,
+        ),
+        periods_per_year,
+    );
+    schedule = round_schedule(schedule, period_interest, strategy, house.rounding);
+    if house.adjustable_rate.enabled {
+        schedule = adjust_rate(schedule, house, strategy, periods_per_year, starts_after);
+    }
+    schedule = gather_into_months(schedule, periods_per_year);
+
+    if house.refinancing.enabled {
+        schedule = refinance(
+            schedule,
+            &house.refinancing,
+            house.yearly_extra_amortization,
+            starts_after,
+            house.rounding,
+        );
+    }
+
+    let before_purchase = vec![0.0; purchased_after as usize];
+
+    schedule.name = house.name.clone();
+    schedule.purchased_after = purchased_after;
+    schedule.down_payment = house.down_payment;
+    schedule.upfront_fees = house.fees.upfront;
+    schedule.starts_after = starts_after;
+    schedule.ends_after += starts_after;
+    schedule.construction_payments =
+        [&before_purchase[..], &construction.builder_payments].concat();
+    schedule.construction_interest = [&before_purchase[..], &construction.interest].concat();
+
+    if house.sale.enabled {
+        sell(&mut schedule, house);
+    }
+
+    charge_fees(&mut schedule, house);
+
+    schedule
+}
+
+/// Fills the charges and the escrow paid with each installment.
+///
+/// `PMI` is charged until the balance before an installment falls to the
+/// maximum loan-to-value of the home's appreciated value, and never again
+/// after that.
+fn charge_fees(schedule: &mut LoanSchedule, house: &House) {
+    let fees = &house.fees;
+    let financed = schedule.balances.first().copied().unwrap_or(0.0);
+    let n_installments = schedule.monthly_payments.len();
+    let mut charges_pmi = fees.pmi_monthly_rate > 0.0;
+
+    schedule.charges = Vec::with_capacity(n_installments);
+    schedule.escrow = Vec::with_capacity(n_installments);
+
+    for (i, balance) in schedule.balances.iter().take(n_installments).enumerate() {
+        let months_held = schedule.starts_after + i as i32 - schedule.purchased_after;
+        let house_value = calculate_house_value_at(house, months_held);
+
+        if charges_pmi && *balance <= house_value * fees.pmi_max_loan_to_value {
+            charges_pmi = false;
+            schedule.pmi_drops_at = Some(schedule.starts_after + i as i32 + 1);
+        }
+
+        let pmi = if charges_pmi {
+            financed * fees.pmi_monthly_rate
+        } else {
+            0.0
+        };
+
+        schedule
+            .charges
+            .push(fees.monthly_fee + balance * fees.monthly_insurance_rate + pmi);
+        schedule
+            .escrow
+            .push(house_value * fees.monthly_property_tax_rate + fees.monthly_homeowners_insurance);
+    }
+}
+
+/// Gets the market value of the house after being held for the given number
+/// of months.
+pub(crate) fn calculate_house_value_at(house: &House, months_held: i32) -> f64 {
+    house.house_price * (1.0 + house.monthly_appreciation).powi(months_held)
+}
+
+/// Converts a monthly rate into the rate of one period between installments.
+///
+/// The monthly rate compounds over a twelfth of the year, so each period
+/// accrues it over its share of the days of the year under `day_count`.
+pub(crate) fn convert_monthly_interest(
+    monthly_interest: f64,
+    frequency: PaymentFrequency,
+    day_count: DayCount,
+) -> f64 {
+    if frequency == PaymentFrequency::Monthly {
+        return monthly_interest;
+    }
+
+    let year_fraction = day_count.days_in_period(frequency) / day_count.days_in_year();
+
+    (1.0 + monthly_interest).powf(12.0 * year_fraction) - 1.0
+}
+
+/// Gets the month, counted from 0, in which the given period ends.
+fn month_of_period(period_index: usize, periods_per_year: i32) -> usize {
+    ((period_index + 1) * 12 - 1) / periods_per_year as usize
+}
+
+/// Moves amounts due at the end of each month to the last period of it.
+fn spread_over_periods(monthly: &[f64], periods_per_year: i32) -> Vec<f64> {
+    if periods_per_year == 12 {
+        return monthly.to_vec();
+    }
+
+    let mut periods = vec![0.0; monthly.len() * periods_per_year as usize / 12];
+
+    for (month_index, amount) in monthly.iter().enumerate() {
+        let period_index = (month_index + 1) * periods_per_year as usize / 12 - 1;
+
+        if let Some(period) = periods.get_mut(period_index) {
+            *period += amount;
+        }
+    }
+
+    periods
+}
+
+/// Sums the installments paid within each month, for loans paid more often
+/// than monthly. Balances are taken after the last installment of the month.
+fn gather_into_months(schedule: LoanSchedule, periods_per_year: i32) -> LoanSchedule {
+    // Nothing is financed.
+    if periods_per_year == 12 || schedule.balances.is_empty() {
+        return schedule;
+    }
+
+    let month_of = |period_index: usize| month_of_period(period_index, periods_per_year);
+    let n_months = schedule
+        .monthly_payments
+        .len()
+        .checked_sub(1)
+        .map_or(0, |last| month_of(last) + 1);
+
+    let mut monthly_payments = vec![0.0; n_months];
+    let mut extra_amortizations = vec![0.0; n_months];
+    let mut balloon_payments = vec![0.0; n_months];
+    let mut balances = vec![0.0; n_months + 1];
+    balances[0] = schedule.balances[0];
+
+    for (i, payment) in schedule.monthly_payments.iter().enumerate() {
+        let month_index = month_of(i);
+
+        monthly_payments[month_index] += payment;
+        extra_amortizations[month_index] += schedule.extra_amortizations[i];
+        balloon_payments[month_index] += schedule.balloon_payments[i];
+        balances[month_index + 1] = schedule.balances[i + 1];
+    }
+
+    LoanSchedule {
+        monthly_payments,
+        extra_amortizations,
+        balloon_payments,
+        balances,
+        ends_after: if schedule.ends_after > 0 {
+            month_of(schedule.ends_after as usize - 1) as i32 + 1
+        } else {
+            0
+        },
+        ..schedule
+    }
+}
+
+/// Sells the house at its appreciated value, stopping every payment from the
+/// month of the sale on and paying off the outstanding balance.
+fn sell(schedule: &mut LoanSchedule, house: &House) {
+    let sale = &house.sale;
+    let month_index = (sale.month - 1).max(schedule.purchased_after) as usize;
+    let months_held = month_index as i32 - schedule.purchased_after;
+    let installments_paid = month_index.saturating_sub(schedule.starts_after as usize);
+
+    let outstanding_balance = schedule.balance_at(month_index);
+
+    schedule.monthly_payments.truncate(installments_paid);
+    schedule.extra_amortizations.truncate(installments_paid);
+    schedule.balloon_payments.truncate(installments_paid);
+    schedule.balances.truncate(installments_paid + 1);
+    schedule.construction_payments.truncate(month_index);
+    schedule.construction_interest.truncate(month_index);
+    schedule.ends_after = schedule.ends_after.min(month_index as i32);
+    schedule
+        .rate_resets
+        .retain(|reset| reset.month <= month_index as i32);
+
+    let sale_price = calculate_house_value_at(house, months_held);
+    let broker_commission = sale_price * sale.broker_commission;
+    let capital_gain = sale_price - broker_commission - house.house_price;
+
+    schedule.sale = Some(SaleReport {
+        month: month_index as i32 + 1,
+        sale_price,
+        broker_commission,
+        outstanding_balance,
+        capital_gain,
+        capital_gains_tax: calculate_capital_gains_tax(
+            capital_gain,
+            months_held,
+            sale.reinvested_fraction,
+        ),
+    });
+}
+
+/// Gets the capital gains tax (`GCAP`) over selling a home.
+///
+/// The gain is reduced for each month the home was held, and the fraction of
+/// the proceeds reinvested in another home within 180 days is exempt.
+fn calculate_capital_gains_tax(
+    capital_gain: f64,
+    months_held: i32,
+    reinvested_fraction: f64,
+) -> f64 {
+    if capital_gain <= 0.0 {
+        return 0.0;
+    }
+
+    let reduced_gain = capital_gain / (1.0 + CAPITAL_GAINS_MONTHLY_REDUCTION).powi(months_held);
+    let taxable_gain = reduced_gain * (1.0 - reinvested_fraction.clamp(0.0, 1.0));
+
+    let mut tax = 0.0;
+    let mut lower_limit = 0.0;
+
+    for (upper_limit, rate) in CAPITAL_GAINS_TAX_TABLE {
+        if taxable_gain <= lower_limit {
+            break;
+        }
+
+        tax += (taxable_gain.min(upper_limit) - lower_limit) * rate;
+        lower_limit = upper_limit;
+    }
+
+    tax
+}
+
+fn calculate_amortization(
+    value: f64,
+    monthly_interest: f64,
+    n_months_to_pay: i32,
+    yearly_extra_amortization: f64,
+    strategy: AmortizationStrategyType,
+    balloons: &[f64],
+    periods_per_year: i32,
+) -> LoanSchedule {
+    match strategy {
+        AmortizationStrategyType::Price => {
+            calculate_loan_schedule_price(value, monthly_interest, n_months_to_pay, balloons)
+        }
+        AmortizationStrategyType::Sac => calculate_loan_schedule_sac(
+            value,
+            monthly_interest,
+            n_months_to_pay,
+            yearly_extra_amortization,
+            balloons,
+            periods_per_year,
+        ),
+    }
+}
+
+/// Starts the loan with a grace period (`carência`), amortizing the balance
+/// left after it over the rest of the term.
+///
+/// Intermediate payments are counted from the end of the grace period.
 #[allow(clippy::too_many_arguments)]
-pub(crate) fn calculate_money_timeseries_price(
-    months_to_forecast: i32,
-    starting_money: f64,
-    down_payment: f64,
-    house_price: f64,
-    house_monthly_interest: f64,
+fn calculate_amortization_after_grace(
+    value: f64,
+    monthly_interest: f64,
     n_months_to_pay: i32,
-    liquid_salary: f64,
-    fixed_monthly_expenses: f64,
-    investment_monthly_interest: f64,
-    yearly_bonus: f64,
-    inflation: f64,
-) -> SimulationOutput {
-    let mut time_series: Vec<f64> = Vec::new();
+    yearly_extra_amortization: f64,
+    strategy: AmortizationStrategyType,
+    grace_period: &GracePeriod,
+    balloons: &[f64],
+    periods_per_year: i32,
+) -> LoanSchedule {
+    let grace_months = if grace_period.enabled && value > 0.0 {
+        grace_period.months.clamp(0, n_months_to_pay - 1)
+    } else {
+        0
+    };
 
-    let mut money_left = starting_money - down_payment;
-    time_series.push(money_left);
+    let mut monthly_payments = Vec::with_capacity(n_months_to_pay as usize);
+    let mut balances = vec![value];
+
+    for _ in 0..grace_months {
+        let balance = balances[balances.len() - 1];
+        let interest = balance * monthly_interest;
 
-    let monthly_payment = calculate_monthly_payment_price_table(
-        house_price - down_payment,
-        house_monthly_interest,
-        n_months_to_pay,
-        ERR,
-        MAX_ITERS,
-        UPPER_BOUND,
+        match grace_period.mode {
+            GracePeriodMode::InterestOnly => {
+                monthly_payments.push(interest);
+                balances.push(balance);
+            }
+            GracePeriodMode::Capitalized => {
+                monthly_payments.push(0.0);
+                balances.push(balance + interest);
+            }
+        }
+    }
+
+    let schedule = calculate_amortization(
+        balances[balances.len() - 1],
+        monthly_interest,
+        n_months_to_pay - grace_months,
+        yearly_extra_amortization,
+        strategy,
+        balloons,
+        periods_per_year,
     );
 
-    for i in 0..(months_to_forecast as usize) {
-        let is_end_of_year = i % 12 == 0 && i > 0;
+    if grace_months == 0 {
+        return schedule;
+    }
 
-        // Subtractions are done before to safely underestimate returns.
-        if i < n_months_to_pay as usize {
-            money_left -= monthly_payment;
+    monthly_payments.extend(schedule.monthly_payments);
+    balances.extend(schedule.balances.into_iter().skip(1));
+
+    LoanSchedule {
+        monthly_payments,
+        extra_amortizations: [
+            vec![0.0; grace_months as usize],
+            schedule.extra_amortizations,
+        ]
+        .concat(),
+        balloon_payments: [vec![0.0; grace_months as usize], schedule.balloon_payments].concat(),
+        balances,
+        ends_after: schedule.ends_after + grace_months,
+        ..Default::default()
+    }
+}
+
+/// Rounds the value to centavos.
+fn round_to_centavos(value: f64, rounding: RoundingMode) -> f64 {
+    // Snaps away the binary noise of values such as 2.675 before rounding.
+    let centavos = (value * 100.0 * 1e6).round() / 1e6;
+
+    match rounding {
+        RoundingMode::None => value,
+        RoundingMode::HalfUp => centavos.round() / 100.0,
+        RoundingMode::HalfEven => centavos.round_ties_even() / 100.0,
+    }
+}
+
+/// Redoes the schedule in centavos, as banks do: the installment (PRICE) or
+/// the amortization (SAC) is rounded, the interest is rounded over the
+/// rounded balance, and the last installment absorbs what is left, so the
+/// balance ends at exactly zero.
+fn round_schedule(
+    schedule: LoanSchedule,
+    monthly_interest: f64,
+    strategy: AmortizationStrategyType,
+    rounding: RoundingMode,
+) -> LoanSchedule {
+    if rounding == RoundingMode::None || schedule.monthly_payments.is_empty() {
+        return schedule;
+    }
+
+    let round = |value: f64| round_to_centavos(value, rounding);
+    let at = |series: &[f64], i: usize| series.get(i).copied().unwrap_or(0.0);
+    let n_installments = schedule.monthly_payments.len();
+
+    let mut balance = round(schedule.balances[0]);
+    let mut monthly_payments = Vec::with_capacity(n_installments);
+    let mut extra_amortizations = Vec::with_capacity(n_installments);
+    let mut balloon_payments = Vec::with_capacity(n_installments);
+    let mut balances = vec![balance];
+
+    for (i, &original_payment) in schedule.monthly_payments.iter().enumerate() {
+        let extra_amortization = round(at(&schedule.extra_amortizations, i));
+        let balloon = round(at(&schedule.balloon_payments, i));
+        let interest = round(balance * monthly_interest);
+
+        let payment = if i + 1 == n_installments {
+            round(interest + balance - balloon - extra_amortization).max(0.0)
+        } else if original_payment == 0.0 {
+            // Capitalized grace period.
+            0.0
+        } else {
+            match strategy {
+                AmortizationStrategyType::Price => round(original_payment),
+                AmortizationStrategyType::Sac => {
+                    let amortization = at(&schedule.balances, i)
+                        - at(&schedule.balances, i + 1)
+                        - at(&schedule.extra_amortizations, i)
+                        - at(&schedule.balloon_payments, i);
+
+                    interest + round(amortization)
+                }
+            }
+        };
+
+        balance = round(balance + interest - payment - balloon - extra_amortization);
+
+        monthly_payments.push(payment);
+        extra_amortizations.push(extra_amortization);
+        balloon_payments.push(balloon);
+        balances.push(balance);
+    }
+
+    LoanSchedule {
+        monthly_payments,
+        extra_amortizations,
+        balloon_payments,
+        balances,
+        ..schedule
+    }
+}
+
+/// Resets the rate of an adjustable-rate loan after its fixed period, and on
+/// every `reset_months` after that, to the index plus the margin within the
+/// caps and the floor.
+///
+/// PRICE installments are recomputed as a new annuity over the balance and
+/// the term left at each reset, while SAC keeps its amortizations, extra
+/// ones included, and only the interest follows the new rate. A grace period
+/// still running goes on at the new rate.
+fn adjust_rate(
+    mut schedule: LoanSchedule,
+    house: &House,
+    strategy: AmortizationStrategyType,
+    periods_per_year: i32,
+    starts_after: i32,
+) -> LoanSchedule {
+    // Nothing is financed.
+    if schedule.monthly_payments.is_empty() {
+        return schedule;
+    }
+
+    let adjustable = &house.adjustable_rate;
+    let to_periods = |months: i32| (months * periods_per_year / 12) as usize;
+    let n_periods = to_periods(house.months_to_pay).max(1);
+    let balloons = spread_over_periods(
+        &calculate_balloon_schedule(&house.intermediate_payments, house.months_to_pay),
+        periods_per_year,
+    );
+    let grace_period = GracePeriod {
+        months: to_periods(house.grace_period.months) as i32,
+        ..house.grace_period
+    };
+    let grace_periods = if grace_period.enabled && schedule.balances[0] > 0.0 {
+        grace_period.months.clamp(0, n_periods as i32 - 1) as usize
+    } else {
+        0
+    };
+
+    let resets = (adjustable.fixed_months.max(1)..house.months_to_pay)
+        .step_by(adjustable.reset_months.max(1) as usize);
+    let mut monthly_interest = house.house_monthly_interest;
+
+    for (i, reset) in resets.enumerate() {
+        let kept = to_periods(reset);
+
+        if kept >= schedule.monthly_payments.len() {
+            break;
         }
 
-        money_left -= fixed_monthly_expenses * (1.0 + inflation).powi(i as i32);
+        let index = adjustable
+            .index_path
+            .get(i)
+            .or(adjustable.index_path.last())
+            .copied()
+            .unwrap_or(0.0);
+
+        monthly_interest = (index + adjustable.margin)
+            .min(monthly_interest + adjustable.periodic_cap)
+            .max(monthly_interest - adjustable.periodic_cap)
+            .min(house.house_monthly_interest + adjustable.lifetime_cap)
+            .max(adjustable.floor);
+
+        let period_interest =
+            convert_monthly_interest(monthly_interest, house.payment_frequency, house.day_count);
+        let balance = schedule.balances[kept];
+        let n_left = (n_periods - kept) as i32;
+
+        // Intermediate payments and yearly extra amortizations are counted
+        // from the end of the grace period, as in the original schedule.
+        let new_loan = if kept < grace_periods {
+            calculate_amortization_after_grace(
+                balance,
+                period_interest,
+                n_left,
+                house.yearly_extra_amortization,
+                strategy,
+                &GracePeriod {
+                    months: (grace_periods - kept) as i32,
+                    ..grace_period
+                },
+                &balloons,
+                periods_per_year,
+            )
+        } else {
+            match strategy {
+                AmortizationStrategyType::Price => calculate_loan_schedule_price(
+                    balance,
+                    period_interest,
+                    n_left,
+                    balloons.get(kept - grace_periods..).unwrap_or(&[]),
+                ),
+                AmortizationStrategyType::Sac => reprice_sac(&schedule, kept, period_interest),
+            }
+        };
+        let new_loan = round_schedule(new_loan, period_interest, strategy, house.rounding);
+
+        schedule.rate_resets.push(RateReset {
+            month: starts_after + reset + 1,
+            monthly_interest,
+            first_payment: new_loan.monthly_payments.first().copied().unwrap_or(0.0),
+        });
+
+        let join = |old: &mut Vec<f64>, new: &[f64]| {
+            old.truncate(kept);
+            old.extend_from_slice(new);
+        };
+
+        join(&mut schedule.monthly_payments, &new_loan.monthly_payments);
+        join(
+            &mut schedule.extra_amortizations,
+            &new_loan.extra_amortizations,
+        );
+        join(&mut schedule.balloon_payments, &new_loan.balloon_payments);
+        join(&mut schedule.balances, &new_loan.balances);
+        schedule.ends_after = kept as i32 + new_loan.ends_after;
+    }
+
+    schedule
+}
+
+/// Gets the SAC installments from the given one on, with the same
+/// amortizations and the interest over the new rate.
+fn reprice_sac(schedule: &LoanSchedule, first: usize, monthly_interest: f64) -> LoanSchedule {
+    let balances = schedule.balances[first..].to_vec();
+    let extra_amortizations = schedule.extra_amortizations[first..].to_vec();
+    let balloon_payments = schedule.balloon_payments[first..].to_vec();
+    let monthly_payments = (0..balances.len() - 1)
+        .map(|i| {
+            let amortization =
+                balances[i] - balances[i + 1] - extra_amortizations[i] - balloon_payments[i];
+
+            balances[i] * monthly_interest + amortization
+        })
+        .collect();
+
+    LoanSchedule {
+        monthly_payments,
+        extra_amortizations,
+        balloon_payments,
+        balances,
+        ends_after: schedule.ends_after - first as i32,
+        ..Default::default()
+    }
+}
+
+/// Carries the outstanding balance of a loan into a new one (`portabilidade`)
+/// at the refinancing month, counted from the start of the simulation.
+///
+/// Does nothing if the loan is already paid by then.
+fn refinance(
+    schedule: LoanSchedule,
+    refinancing: &Refinancing,
+    yearly_extra_amortization: f64,
+    starts_after: i32,
+    rounding: RoundingMode,
+) -> LoanSchedule {
+    let kept = (refinancing.month - 1 - starts_after).max(0) as usize;
+
+    if kept >= schedule.monthly_payments.len() {
+        return schedule;
+    }
+
+    // The intermediate payments and the final balloon left are carried over.
+    let new_loan = calculate_amortization(
+        schedule.balances[kept],
+        refinancing.monthly_interest,
+        refinancing.months_to_pay,
+        yearly_extra_amortization,
+        refinancing.strategy,
+        &schedule.balloon_payments[kept..],
+        12,
+    );
+    let new_loan = round_schedule(
+        new_loan,
+        refinancing.monthly_interest,
+        refinancing.strategy,
+        rounding,
+    );
+
+    let outflows = |loan: &LoanSchedule, from: usize| -> Vec<f64> {
+        loan.monthly_payments[from..]
+            .iter()
+            .zip(&loan.extra_amortizations[from..])
+            .zip(&loan.balloon_payments[from..])
+            .map(|((payment, extra), balloon)| payment + extra + balloon)
+            .collect()
+    };
+
+    let report = RefinancingReport {
+        month: starts_after + kept as i32 + 1,
+        switching_costs: refinancing.switching_costs,
+        original_payments: schedule.monthly_payments[kept..].to_vec(),
+        original_outflows: outflows(&schedule, kept),
+        new_outflows: outflows(&new_loan, 0),
+        new_first_payment: new_loan.monthly_payments.first().copied().unwrap_or(0.0),
+    };
+
+    let join = |old: &[f64], new: &[f64]| [&old[..kept], new].concat();
+
+    LoanSchedule {
+        monthly_payments: join(&schedule.monthly_payments, &new_loan.monthly_payments),
+        extra_amortizations: join(&schedule.extra_amortizations, &new_loan.extra_amortizations),
+        balloon_payments: join(&schedule.balloon_payments, &new_loan.balloon_payments),
+        balances: join(&schedule.balances, &new_loan.balances),
+        ends_after: kept as i32 + new_loan.ends_after,
+        rate_resets: schedule
+            .rate_resets
+            .into_iter()
+            .filter(|reset| reset.month < report.month)
+            .collect(),
+        refinancing: Some(report),
+        ..Default::default()
+    }
+}
 
-        money_left *= 1.0 + investment_monthly_interest;
+struct ConstructionSchedule {
+    months_until_delivery: i32,
+    /// Value financed by the bank, to be amortized after the delivery.
+    financed_value: f64,
+    builder_payments: Vec<f64>,
+    interest: Vec<f64>,
+}
 
-        money_left += liquid_salary;
+/// Gets the payments of a house bought under construction (`na planta`).
+///
+/// Builder payments are corrected by INCC. Their nominal value is discounted
+/// from what the bank finances, which is released linearly to the builder
+/// from the signing of the financing up to the delivery of the keys, with
+/// `juros de obra` charged over the amount released so far.
+fn calculate_construction_schedule(house: &House) -> ConstructionSchedule {
+    let construction = &house.construction;
 
-        if is_end_of_year {
-            money_left += yearly_bonus;
+    if !construction.enabled {
+        return ConstructionSchedule {
+            months_until_delivery: 0,
+            financed_value: house.house_price - house.down_payment - house.subsidy,
+            builder_payments: Vec::new(),
+            interest: Vec::new(),
+        };
+    }
+
+    let months = construction.months_until_delivery;
+    let mut builder_payments = Vec::with_capacity(months as usize);
+    let mut nominal_builder_total = 0.0;
+
+    for month in 1..=months {
+        let mut payment = construction.builder_monthly_payment;
+
+        if construction.months_between_intermediate_payments > 0
+            && month % construction.months_between_intermediate_payments == 0
+        {
+            payment += construction.intermediate_payment;
         }
 
-        time_series.push(money_left);
+        nominal_builder_total += payment;
+        builder_payments.push(payment * (1.0 + construction.incc).powi(month - 1));
     }
 
-    SimulationOutput {
-        time_series,
+    let financed_value =
+        (house.house_price - house.down_payment - house.subsidy - nominal_builder_total).max(0.0);
+
+    let signed_month = construction.financing_signed_month.clamp(1, months.max(1));
+    let release_months = (months - signed_month + 1) as f64;
+    let interest = (1..=months)
+        .map(|month| {
+            if month < signed_month {
+                return 0.0;
+            }
+
+            let released = financed_value * (month - signed_month + 1) as f64 / release_months;
+
+            released * house.house_monthly_interest
+        })
+        .collect();
+
+    ConstructionSchedule {
+        months_until_delivery: months,
+        financed_value,
+        builder_payments,
+        interest,
+    }
+}
+
+/// Intermediate payments are discounted from the value before computing the
+/// installment, so the loan is still paid off on the last month.
+fn calculate_loan_schedule_price(
+    value: f64,
+    monthly_interest: f64,
+    n_months_to_pay: i32,
+    balloons: &[f64],
+) -> LoanSchedule {
+    if value <= 0.0 {
+        return LoanSchedule::default();
+    }
+
+    let balloons_value: f64 = balloons
+        .iter()
+        .take(n_months_to_pay as usize)
+        .enumerate()
+        .map(|(i, balloon)| balloon / (1.0 + monthly_interest).powi(i as i32 + 1))
+        .sum();
+
+    let monthly_payment = if value > balloons_value {
+        calculate_monthly_payment_price_table(
+            value - balloons_value,
+            monthly_interest,
+            n_months_to_pay,
+            ERR,
+            MAX_ITERS,
+            UPPER_BOUND,
+        )
+    } else {
+        0.0
+    };
+
+    let mut balances = Vec::with_capacity(n_months_to_pay as usize + 1);
+    let mut balloon_payments = Vec::with_capacity(n_months_to_pay as usize);
+    balances.push(value);
+
+    for i in 0..(n_months_to_pay as usize) {
+        let value_left = balances[balances.len() - 1] * (1.0 + monthly_interest) - monthly_payment;
+        let balloon = balloons
+            .get(i)
+            .copied()
+            .unwrap_or(0.0)
+            .min(value_left.max(0.0));
+
+        balloon_payments.push(balloon);
+        balances.push((value_left - balloon).max(0.0));
+    }
+
+    LoanSchedule {
         monthly_payments: vec![monthly_payment; n_months_to_pay as usize],
+        extra_amortizations: vec![0.0; n_months_to_pay as usize],
+        balloon_payments,
+        balances,
         ends_after: n_months_to_pay,
+        ..Default::default()
     }
 }
 
-/// Gets the monthly timeseries of money on account after buying house.
-#[allow(clippy::too_many_arguments)]
-pub(crate) fn calculate_money_timeseries_sac(
-    months_to_forecast: i32,
-    starting_money: f64,
-    down_payment: f64,
-    house_price: f64,
-    house_monthly_interest: f64,
+/// Yearly extra amortizations keep the monthly amortization constant,
+/// so they shorten the financing instead of lowering the installments. They
+/// are paid with the last of the `periods_per_year` installments of each year.
+///
+/// Intermediate payments are amortization too, so the constant amortization
+/// covers only what they leave.
+fn calculate_loan_schedule_sac(
+    value: f64,
+    monthly_interest: f64,
     n_months_to_pay: i32,
-    liquid_salary: f64,
-    fixed_monthly_expenses: f64,
-    investment_monthly_interest: f64,
-    yearly_bonus: f64,
     yearly_extra_amortization: f64,
-    inflation: f64,
-) -> SimulationOutput {
-    let mut time_series: Vec<f64> = Vec::new();
+    balloons: &[f64],
+    periods_per_year: i32,
+) -> LoanSchedule {
+    let balloons_total: f64 = balloons.iter().take(n_months_to_pay as usize).sum();
+    let monthly_amortization = (value - balloons_total).max(0.0) / (n_months_to_pay as f64);
+    let mut value_to_pay_left = value;
 
-    let mut money_left = starting_money - down_payment;
-    let mut value_to_pay_left = house_price - down_payment;
-    time_series.push(money_left);
-
-    let monthly_amortization = value_to_pay_left / (n_months_to_pay as f64);
     let mut monthly_payments = Vec::with_capacity(n_months_to_pay as usize);
-
+    let mut extra_amortizations = Vec::with_capacity(n_months_to_pay as usize);
+    let mut balloon_payments = Vec::with_capacity(n_months_to_pay as usize);
+    let mut balances = vec![value_to_pay_left.max(0.0)];
     let mut ends_after = n_months_to_pay;
 
     if value_to_pay_left <= 0.0 {
         ends_after = 0;
     }
 
-    for i in 0..(months_to_forecast as usize).max(n_months_to_pay as usize) {
-        let is_end_of_year = (i + 1) % 12 == 0;
+    for i in 0..(n_months_to_pay as usize) {
+        if value_to_pay_left <= 0.0 {
+            break;
+        }
+
+        let is_end_of_year = (i + 1) % periods_per_year as usize == 0;
+        let amortization = monthly_amortization.min(value_to_pay_left);
 
-        // Subtractions are done before to safely underestimate returns.
-        if value_to_pay_left > 0.0 {
-            if i < n_months_to_pay as usize {
-                money_left -= value_to_pay_left * house_monthly_interest + monthly_amortization;
+        monthly_payments.push(value_to_pay_left * monthly_interest + amortization);
+        value_to_pay_left -= amortization;
+
+        let balloon = balloons
+            .get(i)
+            .copied()
+            .unwrap_or(0.0)
+            .min(value_to_pay_left);
+
+        balloon_payments.push(balloon);
+        value_to_pay_left -= balloon;
+
+        let extra_amortization = if is_end_of_year {
+            yearly_extra_amortization.min(value_to_pay_left)
+        } else {
+            0.0
+        };
+
+        extra_amortizations.push(extra_amortization);
+        value_to_pay_left -= extra_amortization;
+        balances.push(value_to_pay_left.max(0.0));
+
+        if value_to_pay_left <= 0.0 {
+            ends_after = (i + 1) as i32;
+        }
+    }
+
+    LoanSchedule {
+        monthly_payments,
+        extra_amortizations,
+        balloon_payments,
+        balances,
+        ends_after,
+        ..Default::default()
+    }
+}
 
-                monthly_payments
-                    .push(value_to_pay_left * house_monthly_interest + monthly_amortization);
+/// Gets the intermediate payments due on each month of the financing, with
+/// the final balloon on the last one.
+fn calculate_balloon_schedule(
+    intermediate_payments: &IntermediatePayments,
+    n_months_to_pay: i32,
+) -> Vec<f64> {
+    if !intermediate_payments.enabled {
+        return Vec::new();
+    }
+
+    (1..=n_months_to_pay)
+        .map(|month| {
+            let mut balloon = 0.0;
 
-                value_to_pay_left -= monthly_amortization;
+            if intermediate_payments.months_between > 0
+                && month % intermediate_payments.months_between == 0
+            {
+                balloon += intermediate_payments.amount;
             }
 
-            if is_end_of_year {
-                if yearly_extra_amortization >= value_to_pay_left {
-                    money_left -= value_to_pay_left;
-                    value_to_pay_left = 0.0;
-                } else {
-                    money_left -= yearly_extra_amortization;
-                    value_to_pay_left -= yearly_extra_amortization;
-                }
+            if month == n_months_to_pay {
+                balloon += intermediate_payments.final_balloon;
             }
 
-            if value_to_pay_left <= 0.0 {
-                ends_after = (i + 1) as i32;
+            balloon
+        })
+        .collect()
+}
+
+/// A house bought during the simulation, together with its financing.
+struct Holding {
+    house: House,
+    loan: LoanSchedule,
+    is_property: bool,
+}
+
+/// Buys the house of the simulation and, when trading up, buys the next one
+/// with the proceeds of its sale, followed by the additional loans.
+fn buy_houses(
+    house: &House,
+    strategy: AmortizationStrategyType,
+    trade_up: &TradeUp,
+    additional_loans: &[AdditionalLoan],
+) -> Vec<Holding> {
+    let trades_up = trade_up.enabled && house.sale.enabled;
+
+    // The next home takes the proceeds up to what is left of its price, in the
+    // same month, and the rest stays on account.
+    let next_price = (trade_up.house.house_price - trade_up.house.subsidy).max(0.0);
+    let extra_down_payment = trade_up.house.down_payment.clamp(0.0, next_price);
+    let room_for_proceeds = next_price - extra_down_payment;
+
+    let mut holdings = if trades_up {
+        // Only the fraction of the proceeds put into the next home is exempt
+        // from `GCAP`, taken over the proceeds before the tax.
+        let mut first_house = house.clone();
+        first_house.sale.reinvested_fraction = 1.0;
+
+        let proceeds = calculate_loan_schedule(&first_house, strategy, 0)
+            .sale
+            .map_or(0.0, |sale| sale.net_proceeds());
+        first_house.sale.reinvested_fraction = if proceeds > 0.0 {
+            (room_for_proceeds / proceeds).min(1.0)
+        } else {
+            0.0
+        };
+
+        buy_house(first_house, strategy, 0, true)
+    } else {
+        buy_house(house.clone(), strategy, 0, true)
+    };
+
+    let sale = holdings[0]
+        .loan
+        .sale
+        .as_ref()
+        .map(|sale| (sale.month, sale.net_proceeds()));
+
+    if let Some((month, net_proceeds)) = sale.filter(|_| trades_up) {
+        let next_house = House {
+            down_payment: extra_down_payment + net_proceeds.clamp(0.0, room_for_proceeds),
+            ..trade_up.house.clone()
+        };
+
+        holdings.extend(buy_house(next_house, trade_up.strategy, month, true));
+    }
+
+    for additional in additional_loans {
+        holdings.extend(buy_house(
+            additional.house.clone(),
+            additional.strategy,
+            additional.purchased_after,
+            additional.is_property,
+        ));
+    }
+
+    holdings
+}
+
+/// Finances the house, followed by the home-equity loan taken over it, if
+/// any.
+fn buy_house(
+    house: House,
+    strategy: AmortizationStrategyType,
+    purchased_after: i32,
+    is_property: bool,
+) -> Vec<Holding> {
+    let mut loan = calculate_loan_schedule(&house, strategy, purchased_after);
+    let home_equity = is_property
+        .then(|| take_home_equity(&house, &mut loan))
+        .flatten();
+
+    let mut holdings = vec![Holding {
+        house: house.clone(),
+        loan,
+        is_property,
+    }];
+
+    if let Some(home_equity) = home_equity {
+        holdings.push(Holding {
+            house,
+            loan: home_equity,
+            is_property: false,
+        });
+    }
+
+    holdings
+}
+
+/// Takes a home-equity loan (`crédito com garantia de imóvel`) over the
+/// house, limited to a fraction of its appraised value minus what is still
+/// owed on it.
+///
+/// When the house is sold, the loan is paid off with the proceeds.
+fn take_home_equity(house: &House, loan: &mut LoanSchedule) -> Option<LoanSchedule> {
+    let home_equity = &house.home_equity;
+    let month_index = (home_equity.month - 1).max(0) as usize;
+
+    // The house must have been delivered to be given as collateral.
+    if !home_equity.enabled
+        || month_index < loan.starts_after as usize
+        || !loan.owns_house_at(month_index)
+    {
+        return None;
+    }
+
+    let months_held = month_index as i32 - loan.purchased_after;
+    let limit = calculate_house_value_at(house, months_held) * home_equity.max_loan_to_value
+        - loan.balance_at(month_index);
+    let amount = home_equity.amount.min(limit);
+
+    if amount <= 0.0 {
+        return None;
+    }
+
+    let mut equity_loan = round_schedule(
+        calculate_amortization(
+            amount,
+            home_equity.monthly_interest,
+            home_equity.months_to_pay,
+            0.0,
+            home_equity.strategy,
+            &[],
+            12,
+        ),
+        home_equity.monthly_interest,
+        home_equity.strategy,
+        house.rounding,
+    );
+
+    equity_loan.name = format!("Home Equity ({})", house.name);
+    equity_loan.purchased_after = month_index as i32 + 1;
+    equity_loan.disbursement = amount;
+    equity_loan.starts_after = month_index as i32 + 1;
+    equity_loan.ends_after += equity_loan.starts_after;
+
+    if let Some(sale) = &mut loan.sale {
+        sale.outstanding_balance += pay_off(&mut equity_loan, (sale.month - 1) as usize);
+    }
+
+    Some(equity_loan)
+}
+
+/// Stops the payments of the loan on the given month, returning the balance
+/// paid off then.
+fn pay_off(schedule: &mut LoanSchedule, month_index: usize) -> f64 {
+    let installments_paid = month_index.saturating_sub(schedule.starts_after as usize);
+
+    if installments_paid >= schedule.monthly_payments.len() {
+        return 0.0;
+    }
+
+    let outstanding_balance = schedule.balances[installments_paid];
+
+    schedule.monthly_payments.truncate(installments_paid);
+    schedule.extra_amortizations.truncate(installments_paid);
+    schedule.balloon_payments.truncate(installments_paid);
+    schedule.balances.truncate(installments_paid + 1);
+    schedule.balances.push(0.0);
+    schedule.ends_after = schedule.ends_after.min(month_index as i32);
+
+    outstanding_balance
+}
+
+/// Gets the monthly timeseries of money on account after buying house.
+///
+/// The buyer pays their `installment_share` of the houses and receives the
+/// same share of the rent, and the `FGTS` pays for what it can of the down
+/// payments of the homes.
+pub(crate) fn calculate_money_timeseries(
+    buyer: &Buyer,
+    house: &House,
+    simulation: &Simulation,
+    strategy: AmortizationStrategyType,
+    trade_up: &TradeUp,
+    additional_loans: &[AdditionalLoan],
+) -> SimulationOutput {
+    let months_to_forecast = simulation.months_to_forecast as usize;
+    let holdings = buy_houses(house, strategy, trade_up, additional_loans);
+
+    let share = buyer.installment_share;
+
+    let mut time_series: Vec<f64> = Vec::with_capacity(months_to_forecast + 1);
+
+    // Down payments of homes bought on the given number of months, which the
+    // `FGTS` may pay.
+    let home_down_payments = |purchased_after: i32| -> f64 {
+        holdings
+            .iter()
+            .filter(|holding| {
+                holding.is_property && holding.loan.purchased_after == purchased_after
+            })
+            .map(|holding| holding.loan.down_payment * share)
+            .sum()
+    };
+
+    let mut fgts = buyer.fgts_balance;
+    let fgts_used = fgts.min(home_down_payments(0));
+    fgts -= fgts_used;
+
+    let mut money_left = buyer.starting_money + fgts_used
+        - holdings
+            .iter()
+            .filter(|holding| holding.loan.purchased_after == 0)
+            .map(|holding| (holding.loan.down_payment + holding.loan.upfront_fees) * share)
+            .sum::<f64>();
+    time_series.push(money_left);
+
+    let calculate_net_worth = |money: f64, months_elapsed: usize| {
+        holdings.iter().fold(money, |net_worth, holding| {
+            let loan = &holding.loan;
+
+            if !loan.owns_house_at(months_elapsed) {
+                return net_worth;
+            }
+
+            let months_held = months_elapsed as i32 - loan.purchased_after;
+            let value = if holding.is_property {
+                calculate_house_value_at(&holding.house, months_held)
+            } else {
+                0.0
+            };
+
+            net_worth + (value - loan.balance_at(months_elapsed)) * share
+        })
+    };
+
+    let mut net_worth = Vec::with_capacity(months_to_forecast + 1);
+    net_worth.push(calculate_net_worth(money_left + fgts, 0));
+
+    let mut first_negative_month = (money_left < 0.0).then_some(0);
+    let mut monthly_credit_interest = Vec::with_capacity(months_to_forecast);
+    let mut monthly_outflows = Vec::with_capacity(months_to_forecast);
+    let mut cash_flow_breakdown: Vec<(String, Vec<f64>)> = buyer
+        .cash_flows
+        .iter()
+        .map(|item| (item.name.clone(), Vec::with_capacity(months_to_forecast)))
+        .collect();
+    let mut general_expenses = Vec::with_capacity(months_to_forecast);
+    let mut expense_breakdown: Vec<(String, Vec<f64>)> = buyer
+        .expense_categories
+        .iter()
+        .map(|category| {
+            (
+                category.name.clone(),
+                Vec::with_capacity(months_to_forecast),
+            )
+        })
+        .collect();
+    let mut rental_income = Vec::with_capacity(months_to_forecast);
+    let mut rental_taxes = Vec::with_capacity(months_to_forecast);
+
+    let retirement = &buyer.retirement;
+    let retirement_month = retirement
+        .enabled
+        .then(|| retirement.months_until(buyer.age) as usize)
+        .filter(|month| *month < months_to_forecast);
+    let mut withdrawals = Vec::new();
+    let mut allowed_withdrawals = Vec::new();
+    let mut expense_cuts = Vec::new();
+
+    for i in 0..months_to_forecast {
+        let month = (i + 1) as i32;
+        let (_, calendar_month) = simulation.date_at(i as i32);
+        let is_retired = retirement_month.is_some_and(|retirement_month| i >= retirement_month);
+
+        let general_expense =
+            buyer.fixed_monthly_expenses * (1.0 + simulation.inflation).powi(i as i32);
+        general_expenses.push(general_expense);
+
+        let mut outflow = general_expense;
+        let mut one_off_outflow = 0.0;
+        let mut taxable_rent = 0.0;
+        // The pension replaces the salary, and counts for the 13th.
+        let salary = if is_retired {
+            retirement.monthly_pension * (1.0 + simulation.inflation).powi(i as i32)
+        } else {
+            buyer.liquid_salary
+        };
+        let mut income = salary;
+
+        for holding in &holdings {
+            let loan = &holding.loan;
+
+            outflow += (loan.payment_at(i)
+                + loan.balloon_payment_at(i)
+                + loan.charges_at(i)
+                + loan.escrow_at(i)
+                + loan.construction_payment_at(i))
+                * share;
+
+            if (loan.purchased_after as usize..loan.starts_after as usize).contains(&i) {
+                outflow += holding.house.construction.monthly_rent
+                    * (1.0 + simulation.inflation).powi(i as i32)
+                    * share;
             }
+
+            one_off_outflow += (loan.extra_amortization_at(i)
+                + loan.refinancing_costs_at(i)
+                + loan.down_payment_at(i)
+                - loan.disbursement_at(i)
+                - loan.sale_proceeds_at(i))
+                * share;
+
+            if holding.is_property && loan.owns_house_at(i + 1) {
+                taxable_rent += calculate_rent_at(&holding.house.rental, month) * share;
+            }
+        }
+
+        let fgts_used = fgts.min(home_down_payments(month));
+        fgts = (fgts - fgts_used) * (1.0 + FGTS_MONTHLY_INTEREST);
+        if !is_retired {
+            fgts += buyer.fgts_monthly_deposit;
+        }
+        for deposit in &buyer.fgts_deposits {
+            fgts += calculate_cash_flow_at(deposit, month, simulation.inflation);
         }
+        one_off_outflow -= fgts_used;
 
-        money_left -= fixed_monthly_expenses * (1.0 + inflation).powi(i as i32);
+        for (category, (_, breakdown)) in
+            buyer.expense_categories.iter().zip(&mut expense_breakdown)
+        {
+            let expense = calculate_expense_at(category, i as i32, simulation.inflation);
 
-        money_left *= 1.0 + investment_monthly_interest;
+            outflow += expense;
+            breakdown.push(expense);
+        }
 
-        money_left += liquid_salary;
+        for (item, (_, breakdown)) in buyer.cash_flows.iter().zip(&mut cash_flow_breakdown) {
+            let amount = calculate_cash_flow_at(item, month, simulation.inflation);
 
-        if is_end_of_year {
-            money_left += yearly_bonus;
+            if amount < 0.0 {
+                outflow -= amount;
+            } else {
+                income += amount;
+            }
+
+            breakdown.push(amount);
+        }
+
+        let rental_tax = calculate_income_tax(taxable_rent);
+        income += taxable_rent - rental_tax;
+        rental_income.push(taxable_rent - rental_tax);
+        rental_taxes.push(rental_tax);
+
+        if calendar_month == buyer.bonus_month && !is_retired {
+            income += buyer.yearly_bonus;
         }
 
-        if i <= months_to_forecast as usize {
-            time_series.push(money_left);
+        if buyer.thirteenth_salary && (calendar_month == 11 || calendar_month == 12) {
+            income += salary / 2.0;
         }
+
+        // The general expenses are cut for the withdrawal to fit the rule.
+        if let Some(retirement_month) = retirement_month.filter(|_| is_retired) {
+            let yearly_base = match retirement.withdrawal_rule {
+                WithdrawalRule::AsNeeded => None,
+                WithdrawalRule::FixedRate => Some(
+                    time_series[retirement_month]
+                        * (1.0 + simulation.inflation).powi((i - retirement_month) as i32),
+                ),
+                WithdrawalRule::PercentOfBalance => Some(money_left),
+            };
+
+            if let Some(yearly_base) = yearly_base {
+                let allowed = (yearly_base * retirement.yearly_withdrawal_rate / 12.0).max(0.0);
+                let needed = outflow + one_off_outflow - income;
+                let cut = (needed - allowed).clamp(0.0, general_expense);
+
+                outflow -= cut;
+                if let Some(general_expense) = general_expenses.last_mut() {
+                    *general_expense -= cut;
+                }
+
+                allowed_withdrawals.push(allowed);
+                expense_cuts.push(cut);
+            }
+
+            withdrawals.push((outflow + one_off_outflow - income).max(0.0));
+        }
+
+        // Subtractions are done before to safely underestimate returns.
+        money_left -= outflow + one_off_outflow;
+        monthly_outflows.push(outflow);
+
+        let credit_interest;
+        (money_left, credit_interest) = apply_monthly_interest(
+            money_left,
+            buyer.investment_monthly_interest,
+            buyer.overdraft_monthly_interest,
+        );
+        monthly_credit_interest.push(credit_interest);
+
+        money_left += income;
+
+        if money_left < 0.0 && first_negative_month.is_none() {
+            first_negative_month = Some(month);
+        }
+
+        time_series.push(money_left);
+        net_worth.push(calculate_net_worth(money_left + fgts, i + 1));
     }
 
+    let rented: Vec<&Holding> = holdings
+        .iter()
+        .filter(|holding| holding.is_property && holding.house.rental.enabled)
+        .collect();
+
+    let rental_yield_on_cost = rented
+        .iter()
+        .map(|holding| holding.house.rental.start_month)
+        .min()
+        .map(|start_month| {
+            let first_year: Vec<f64> = rental_income
+                .iter()
+                .skip((start_month - 1).max(0) as usize)
+                .take(12)
+                .copied()
+                .collect();
+            let yearly_rent =
+                first_year.iter().sum::<f64>() * 12.0 / first_year.len().max(1) as f64;
+
+            yearly_rent
+                / rented
+                    .iter()
+                    .map(|holding| holding.house.house_price)
+                    .sum::<f64>()
+        });
+
+    let retirement_report = retirement_month.map(|month| {
+        let target_month = retirement.months_until_target(buyer.age);
+        let depleted_at = (month + 1..time_series.len())
+            .find(|&i| time_series[i] < 0.0)
+            .map(|i| i as i32);
+        let lasts_until_target = match depleted_at {
+            Some(depleted_at) if depleted_at <= target_month => Some(false),
+            _ if target_month <= months_to_forecast as i32 => Some(true),
+            _ => None,
+        };
+
+        RetirementReport {
+            name: buyer.name.clone(),
+            month: month as i32,
+            savings: time_series[month],
+            withdrawals,
+            allowed_withdrawals,
+            expense_cuts,
+            depleted_at,
+            target_month,
+            lasts_until_target,
+        }
+    });
+
     SimulationOutput {
         time_series,
-        monthly_payments,
-        ends_after,
+        net_worth,
+        loans: holdings.into_iter().map(|holding| holding.loan).collect(),
+        first_negative_month,
+        monthly_credit_interest,
+        monthly_outflows,
+        cash_flow_breakdown,
+        general_expenses,
+        expense_breakdown,
+        rental_income,
+        rental_taxes,
+        rental_yield_on_cost,
+        accounts: Vec::new(),
+        retirements: retirement_report.into_iter().collect(),
+    }
+}
+
+/// Gets the rent received on the given month, over which `carnê-leão` is due.
+///
+/// Vacancy is spread over all months, lowering the rent by `vacancy_rate`,
+/// and the agency fee is deducted from the taxable income.
+fn calculate_rent_at(rental: &Rental, month: i32) -> f64 {
+    if !rental.enabled || month < rental.start_month {
+        return 0.0;
+    }
+
+    let years_elapsed = (month - rental.start_month) / 12;
+    let rent = rental.monthly_rent
+        * (1.0 + rental.yearly_adjustment).powi(years_elapsed)
+        * (1.0 - rental.vacancy_rate);
+
+    rent * (1.0 - rental.agency_fee)
+}
+
+/// Gets the monthly income tax over the given income, with the progressive
+/// table used by `carnê-leão`.
+fn calculate_income_tax(income: f64) -> f64 {
+    let (_, rate, deduction) = INCOME_TAX_TABLE
+        .iter()
+        .find(|(upper_limit, _, _)| income <= *upper_limit)
+        .copied()
+        .unwrap_or(INCOME_TAX_TABLE[INCOME_TAX_TABLE.len() - 1]);
+
+    (income * rate - deduction).max(0.0)
+}
+
+/// Gets the expense of a category after `months_elapsed` months of growth.
+fn calculate_expense_at(category: &ExpenseCategory, months_elapsed: i32, inflation: f64) -> f64 {
+    let monthly_growth = match category.growth {
+        ExpenseGrowth::InflationPlusSpread => (1.0 + inflation) * (1.0 + category.monthly_rate),
+        ExpenseGrowth::Custom => 1.0 + category.monthly_rate,
+    };
+
+    category.monthly_amount * monthly_growth.powi(months_elapsed)
+}
+
+/// Gets the signed value of a cash-flow item on the given month.
+///
+/// Indexed amounts grow from the start of the simulation, so `amount` is
+/// always expressed in today's money.
+fn calculate_cash_flow_at(item: &CashFlowItem, month: i32, inflation: f64) -> f64 {
+    let is_active = month >= item.start_month && item.end_month.map_or(true, |end| month <= end);
+
+    let is_due = is_active
+        && match item.frequency {
+            CashFlowFrequency::Monthly => true,
+            CashFlowFrequency::Yearly => (month - item.start_month) % 12 == 0,
+            CashFlowFrequency::OneOff => month == item.start_month,
+        };
+
+    if !is_due {
+        return 0.0;
+    }
+
+    let monthly_rate = match item.indexation {
+        Indexation::None => 0.0,
+        Indexation::Inflation => inflation,
+        Indexation::Custom => item.custom_monthly_rate,
+    };
+    let amount = item.amount * (1.0 + monthly_rate).powi(month - 1);
+
+    if item.is_income {
+        amount
+    } else {
+        -amount
+    }
+}
+
+/// Checks the money on account against a minimum reserve of
+/// `minimum_reserve_months` times the current monthly outflow.
+///
+/// The runway of a point in time is the money on account divided by the
+/// outflow of the month that follows it, i.e., how many months the buyer
+/// would last without any income.
+pub(crate) fn calculate_reserve_report(
+    sim_output: &SimulationOutput,
+    minimum_reserve_months: f64,
+) -> ReserveReport {
+    let last_outflow = sim_output.monthly_outflows.last().copied().unwrap_or(0.0);
+
+    let mut reserve_line = Vec::with_capacity(sim_output.time_series.len());
+    let mut runway = Vec::with_capacity(sim_output.time_series.len());
+    let mut violations = Vec::new();
+    let mut worst_shortfall: f64 = 0.0;
+
+    for (month, money) in sim_output.time_series.iter().enumerate() {
+        let outflow = sim_output
+            .monthly_outflows
+            .get(month)
+            .copied()
+            .unwrap_or(last_outflow);
+        let reserve = minimum_reserve_months * outflow;
+
+        reserve_line.push(reserve);
+        runway.push(if outflow > 0.0 {
+            money / outflow
+        } else {
+            f64::INFINITY
+        });
+
+        if *money < reserve {
+            violations.push(month as i32);
+            worst_shortfall = worst_shortfall.max(reserve - money);
+        }
+    }
+
+    ReserveReport {
+        reserve_line,
+        runway,
+        violations,
+        worst_shortfall,
+    }
+}
+
+/// Applies one month of interest over the money on account.
+///
+/// Positive balances yield `investment_monthly_interest`, while negative
+/// balances are charged `overdraft_monthly_interest`, as with `cheque especial`
+/// or a personal loan. Returns the new balance and the credit interest charged.
+fn apply_monthly_interest(
+    money: f64,
+    investment_monthly_interest: f64,
+    overdraft_monthly_interest: f64,
+) -> (f64, f64) {
+    if money < 0.0 {
+        let credit_interest = -money * overdraft_monthly_interest;
+
+        (money - credit_interest, credit_interest)
+    } else {
+        (money * (1.0 + investment_monthly_interest), 0.0)
     }
 }
 
@@ -218,6 +2141,98 @@ fn _calculate_monthly_payments_sac_table(
     monthly_payments
 }
 
+/// Finds the smallest term in which the installment pays off the value, up
+/// to `max_months`.
+///
+/// For SAC, the installment is the first one, the greatest of the loan.
+pub(crate) fn solve_term(
+    value: f64,
+    monthly_interest: f64,
+    installment: f64,
+    strategy: AmortizationStrategyType,
+    max_months: i32,
+) -> Option<i32> {
+    if value <= 0.0 || installment <= value * monthly_interest {
+        return None;
+    }
+
+    let n_months = match strategy {
+        AmortizationStrategyType::Price => (1..=max_months).find(|&n_months| {
+            calculate_left(installment, value, monthly_interest, n_months) < ERR
+        })?,
+        AmortizationStrategyType::Sac => {
+            (value / (installment - value * monthly_interest)).ceil() as i32
+        }
+    };
+
+    (n_months <= max_months).then_some(n_months.max(1))
+}
+
+/// Finds the monthly interest implied by the installment the bank quotes.
+///
+/// For SAC, the installment is the first one, the greatest of the loan.
+pub(crate) fn solve_rate(
+    value: f64,
+    n_months: i32,
+    installment: f64,
+    strategy: AmortizationStrategyType,
+) -> Option<f64> {
+    if value <= 0.0 || n_months <= 0 || installment < value / n_months as f64 {
+        return None;
+    }
+
+    match strategy {
+        AmortizationStrategyType::Price => {
+            let mut a = 0.0;
+            let mut b = 1.0;
+
+            if calculate_left(installment, value, b, n_months) < 0.0 {
+                return None;
+            }
+
+            for _ in 0..MAX_ITERS {
+                let c = (a + b) / 2.0;
+                let left = calculate_left(installment, value, c, n_months);
+
+                if left.abs() < ERR {
+                    return Some(c);
+                }
+
+                if left > 0.0 {
+                    b = c;
+                } else {
+                    a = c;
+                }
+            }
+
+            Some((a + b) / 2.0)
+        }
+        AmortizationStrategyType::Sac => Some((installment - value / n_months as f64) / value),
+    }
+}
+
+/// Finds the greatest value the installment can finance.
+///
+/// For SAC, the installment is the first one, the greatest of the loan.
+pub(crate) fn solve_value(
+    monthly_interest: f64,
+    n_months: i32,
+    installment: f64,
+    strategy: AmortizationStrategyType,
+) -> f64 {
+    if n_months <= 0 {
+        return 0.0;
+    }
+
+    match strategy {
+        AmortizationStrategyType::Price if monthly_interest > 0.0 => {
+            installment * (1.0 - (1.0 + monthly_interest).powi(-n_months)) / monthly_interest
+        }
+        AmortizationStrategyType::Price => installment * n_months as f64,
+        AmortizationStrategyType::Sac => installment / (1.0 / n_months as f64 + monthly_interest),
+    }
+}
+
 /// Calculates how much money is left to be paid with a given monthly payment.
 ///
 /// If a greater than zero value is left at the end, it finishes
@@ -239,6 +2254,24 @@ fn calculate_left(monthly_payment: f64, total: f64, monthly_interest: f64, n_mon
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::{AdjustableRate, DayCount, PaymentFrequency};
+    use crate::model::{ConstructionPhase, HomeEquity, LoanFees, Retirement, Sale};
+
+    fn no_house() -> House {
+        House {
+            house_price: 0.0,
+            down_payment: 0.0,
+            ..Default::default()
+        }
+    }
+
+    fn simulation(months_to_forecast: i32) -> Simulation {
+        Simulation {
+            months_to_forecast,
+            inflation: 0.0,
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn test_calculate_left() {
@@ -264,6 +2297,1265 @@ mod tests {
         assert!(calculate_left(300.0, 1_000.0, 0.1, 3) - 371.8 < 0.001);
     }
 
+    #[test]
+    fn test_apply_monthly_interest() {
+        let (money, credit_interest) = apply_monthly_interest(1_000.0, 0.01, 0.08);
+        assert!((money - 1_010.0).abs() < 0.001);
+        assert_eq!(credit_interest, 0.0);
+
+        let (money, credit_interest) = apply_monthly_interest(-1_000.0, 0.01, 0.08);
+        assert!((money + 1_080.0).abs() < 0.001);
+        assert!((credit_interest - 80.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_overdraft_is_reported() {
+        // Spends 1,000 every month with no income, starting with 2,500.
+        let buyer = Buyer {
+            starting_money: 2_500.0,
+            liquid_salary: 0.0,
+            fixed_monthly_expenses: 1_000.0,
+            investment_monthly_interest: 0.0,
+            overdraft_monthly_interest: 0.1,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &no_house(),
+            &simulation(6),
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[],
+        );
+
+        // 2,500 -> 1,500 -> 500 -> -550
+        assert_eq!(output.first_negative_month, Some(3));
+        // -550 -> -1,705 -> -2,975.5 -> -4,373.05
+        assert!((output.time_series[6] + 4_373.05).abs() < 0.01);
+        assert!((output.total_credit_interest() - 873.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_calendar_months() {
+        let buyer = Buyer {
+            starting_money: 0.0,
+            liquid_salary: 1_000.0,
+            fixed_monthly_expenses: 1_000.0,
+            investment_monthly_interest: 0.0,
+            overdraft_monthly_interest: 0.0,
+            yearly_bonus: 5_000.0,
+            bonus_month: 12,
+            thirteenth_salary: true,
+            ..Default::default()
+        };
+        let simulation = Simulation {
+            start_year: 2026,
+            start_month: 7,
+            ..simulation(12)
+        };
+        assert_eq!(simulation.date_at(5), (2026, 12));
+        assert_eq!(simulation.date_at(6), (2027, 1));
+        assert_eq!(simulation.date_at(-7), (2025, 12));
+
+        let output = calculate_money_timeseries(
+            &buyer,
+            &no_house(),
+            &simulation,
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[],
+        );
+
+        // Half of the 13th salary in November, the other half and the bonus
+        // in December, and nothing else through June.
+        assert_eq!(output.time_series[4], 0.0);
+        assert_eq!(output.time_series[5], 500.0);
+        assert_eq!(output.time_series[6], 6_000.0);
+        assert_eq!(output.time_series[12], 6_000.0);
+    }
+
+    #[test]
+    fn test_retirement() {
+        // Retires after a year, when the pension pays half of the expenses.
+        let buyer = |retirement: Retirement| Buyer {
+            age: 64,
+            starting_money: 0.0,
+            liquid_salary: 2_000.0,
+            fixed_monthly_expenses: 1_000.0,
+            investment_monthly_interest: 0.0,
+            overdraft_monthly_interest: 0.0,
+            fgts_monthly_deposit: 100.0,
+            retirement: Retirement {
+                enabled: true,
+                age: 65,
+                monthly_pension: 500.0,
+                target_age: 66,
+                ..retirement
+            },
+            ..Default::default()
+        };
+        let simulate = |buyer: &Buyer| {
+            calculate_money_timeseries(
+                buyer,
+                &no_house(),
+                &simulation(36),
+                AmortizationStrategyType::Sac,
+                &TradeUp::default(),
+                &[],
+            )
+        };
+
+        let output = simulate(&buyer(Retirement::default()));
+        let retirement = &output.retirements[0];
+        assert_eq!(output.time_series[12], 12_000.0);
+        assert_eq!(output.time_series[36], 0.0);
+        assert_eq!(retirement.month, 12);
+        assert_eq!(retirement.savings, 12_000.0);
+        assert_eq!(retirement.withdrawals, vec![500.0; 24]);
+        assert!(retirement.allowed_withdrawals.is_empty());
+        assert_eq!(retirement.lasts_until_target, Some(true));
+        // The deposits stop on retirement.
+        assert!((output.net_worth[36] - output.net_worth[12] + 12_000.0).abs() < 200.0);
+
+        // 4% a year of the savings on retirement, which the buyer exceeds.
+        let output = simulate(&buyer(Retirement {
+            withdrawal_rule: WithdrawalRule::FixedRate,
+            yearly_withdrawal_rate: 0.04,
+            ..Default::default()
+        }));
+        let retirement = &output.retirements[0];
+        assert_eq!(retirement.allowed_withdrawals, vec![40.0; 24]);
+        assert_eq!(retirement.withdrawals, vec![40.0; 24]);
+        assert_eq!(retirement.expense_cuts, vec![460.0; 24]);
+        assert_eq!(retirement.months_over_rule(), 0);
+        assert_eq!(output.general_expenses[12], 540.0);
+        assert_eq!(output.time_series[36], 12_000.0 - 24.0 * 40.0);
+
+        // Without a pension, the savings run out after a year.
+        let output = simulate(&Buyer {
+            retirement: Retirement {
+                monthly_pension: 0.0,
+                target_age: 67,
+                ..buyer(Retirement::default()).retirement
+            },
+            ..buyer(Retirement::default())
+        });
+        let retirement = &output.retirements[0];
+        assert_eq!(retirement.depleted_at, Some(25));
+        assert_eq!(retirement.lasts_until_target, Some(false));
+
+        // The simulation ends before the target age.
+        let output = simulate(&Buyer {
+            retirement: Retirement {
+                target_age: 90,
+                ..buyer(Retirement::default()).retirement
+            },
+            ..buyer(Retirement::default())
+        });
+        assert_eq!(output.retirements[0].lasts_until_target, None);
+    }
+
+    #[test]
+    fn test_reserve_report() {
+        // No loan, 1,000 of expenses a month and no income, starting with 8,000.
+        let buyer = Buyer {
+            starting_money: 8_000.0,
+            liquid_salary: 0.0,
+            fixed_monthly_expenses: 1_000.0,
+            investment_monthly_interest: 0.0,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &no_house(),
+            &simulation(4),
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[],
+        );
+        let report = calculate_reserve_report(&output, 6.0);
+
+        // 8,000 -> 7,000 -> 6,000 -> 5,000 -> 4,000
+        assert_eq!(report.runway, vec![8.0, 7.0, 6.0, 5.0, 4.0]);
+        assert_eq!(report.reserve_line, vec![6_000.0; 5]);
+        assert_eq!(report.violations, vec![3, 4]);
+        assert_eq!(report.worst_shortfall, 2_000.0);
+    }
+
+    #[test]
+    fn test_cash_flow_items() {
+        let buyer = Buyer {
+            starting_money: 0.0,
+            liquid_salary: 0.0,
+            fixed_monthly_expenses: 0.0,
+            investment_monthly_interest: 0.0,
+            cash_flows: vec![
+                CashFlowItem {
+                    name: "Carro".to_string(),
+                    amount: 100.0,
+                    is_income: false,
+                    start_month: 2,
+                    end_month: Some(3),
+                    frequency: CashFlowFrequency::Monthly,
+                    indexation: Indexation::None,
+                    custom_monthly_rate: 0.0,
+                },
+                CashFlowItem {
+                    name: "Aluguel".to_string(),
+                    amount: 1_000.0,
+                    is_income: true,
+                    start_month: 1,
+                    end_month: None,
+                    frequency: CashFlowFrequency::Yearly,
+                    indexation: Indexation::Custom,
+                    custom_monthly_rate: 0.01,
+                },
+                CashFlowItem {
+                    name: "Viagem".to_string(),
+                    amount: 50.0,
+                    is_income: false,
+                    start_month: 4,
+                    end_month: None,
+                    frequency: CashFlowFrequency::OneOff,
+                    indexation: Indexation::Inflation,
+                    custom_monthly_rate: 0.0,
+                },
+            ],
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &no_house(),
+            &simulation(14),
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[],
+        );
+
+        let mut car = vec![0.0; 14];
+        car[1] = -100.0;
+        car[2] = -100.0;
+        assert_eq!(output.cash_flow_breakdown[0].1, car);
+
+        let rent = &output.cash_flow_breakdown[1].1;
+        assert_eq!(rent[0], 1_000.0);
+        assert!((rent[12] - 1_000.0 * 1.01_f64.powi(12)).abs() < 0.001);
+        assert_eq!(rent.iter().filter(|amount| **amount != 0.0).count(), 2);
+
+        let mut trip = vec![0.0; 14];
+        trip[3] = -50.0;
+        assert_eq!(output.cash_flow_breakdown[2].1, trip);
+
+        assert_eq!(output.monthly_outflows[1], 100.0);
+        assert_eq!(output.monthly_outflows[3], 50.0);
+        assert!((output.time_series[14] - (1_000.0 + rent[12] - 250.0)).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_expense_categories() {
+        let buyer = Buyer {
+            starting_money: 0.0,
+            liquid_salary: 0.0,
+            fixed_monthly_expenses: 1_000.0,
+            investment_monthly_interest: 0.0,
+            expense_categories: vec![
+                ExpenseCategory {
+                    name: "Saúde".to_string(),
+                    monthly_amount: 500.0,
+                    growth: ExpenseGrowth::InflationPlusSpread,
+                    monthly_rate: 0.005,
+                },
+                ExpenseCategory {
+                    name: "Condomínio".to_string(),
+                    monthly_amount: 800.0,
+                    growth: ExpenseGrowth::Custom,
+                    monthly_rate: 0.002,
+                },
+            ],
+            ..Default::default()
+        };
+        let simulation = Simulation {
+            months_to_forecast: 13,
+            inflation: 0.004,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &no_house(),
+            &simulation,
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[],
+        );
+
+        let health = 500.0 * (1.004_f64 * 1.005).powi(12);
+        let condo = 800.0 * 1.002_f64.powi(12);
+        let general = 1_000.0 * 1.004_f64.powi(12);
+
+        assert_eq!(output.expense_breakdown[0].1[0], 500.0);
+        assert!((output.expense_breakdown[0].1[12] - health).abs() < 0.001);
+        assert!((output.expense_breakdown[1].1[12] - condo).abs() < 0.001);
+        assert!((output.general_expenses[12] - general).abs() < 0.001);
+        assert!((output.monthly_outflows[12] - (health + condo + general)).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_deflated_output_keeps_construction_offset() {
+        let house = House {
+            construction: ConstructionPhase {
+                enabled: true,
+                months_until_delivery: 12,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let simulation = Simulation {
+            months_to_forecast: 24,
+            inflation: 0.01,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &Buyer::default(),
+            &house,
+            &simulation,
+            AmortizationStrategyType::Price,
+            &TradeUp::default(),
+            &[],
+        );
+        let real_output = output.deflated(simulation.inflation);
+
+        assert!(
+            (real_output.loans[0].monthly_payments[0]
+                - output.loans[0].monthly_payments[0] / 1.01_f64.powi(12))
+            .abs()
+                < 0.001
+        );
+    }
+
+    #[test]
+    fn test_deflated_output() {
+        let buyer = Buyer {
+            starting_money: 10_000.0,
+            liquid_salary: 1_000.0,
+            fixed_monthly_expenses: 1_000.0,
+            investment_monthly_interest: 0.0,
+            ..Default::default()
+        };
+        let simulation = Simulation {
+            months_to_forecast: 24,
+            inflation: 0.01,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &no_house(),
+            &simulation,
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[],
+        );
+        let real_output = output.deflated(simulation.inflation);
+
+        // Expenses following inflation are constant in today's money.
+        assert!(real_output
+            .general_expenses
+            .iter()
+            .all(|expense| (expense - 1_000.0).abs() < 0.001));
+        assert!(
+            (real_output.time_series[24] - output.time_series[24] / 1.01_f64.powi(24)).abs()
+                < 0.001
+        );
+        assert_eq!(real_output.time_series[0], output.time_series[0]);
+    }
+
+    #[test]
+    fn test_construction_phase() {
+        let house = House {
+            house_price: 100_000.0,
+            down_payment: 10_000.0,
+            house_monthly_interest: 0.01,
+            months_to_pay: 10,
+            construction: ConstructionPhase {
+                enabled: true,
+                months_until_delivery: 4,
+                builder_monthly_payment: 1_000.0,
+                intermediate_payment: 5_000.0,
+                months_between_intermediate_payments: 2,
+                incc: 0.01,
+                financing_signed_month: 3,
+                monthly_rent: 0.0,
+            },
+            ..Default::default()
+        };
+        let schedule = calculate_loan_schedule(&house, AmortizationStrategyType::Sac, 0);
+
+        // 1,000 a month plus 5,000 on months 2 and 4, corrected by INCC.
+        let expected_builder_payments = [
+            1_000.0,
+            6_000.0 * 1.01,
+            1_000.0 * 1.0201,
+            6_000.0 * 1.030301,
+        ];
+        for (payment, expected) in schedule
+            .construction_payments
+            .iter()
+            .zip(expected_builder_payments)
+        {
+            assert!((payment - expected).abs() < 0.001);
+        }
+
+        // 76,000 are financed, released in halves on months 3 and 4.
+        assert_eq!(schedule.construction_interest, vec![0.0, 0.0, 380.0, 760.0]);
+
+        assert_eq!(schedule.starts_after, 4);
+        assert_eq!(schedule.ends_after, 14);
+        assert_eq!(schedule.payment_at(3), 0.0);
+        assert!((schedule.payment_at(4) - 8_360.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_calculate_income_tax() {
+        assert_eq!(calculate_income_tax(2_000.0), 0.0);
+        assert!((calculate_income_tax(2_500.0) - (2_500.0 * 0.075 - 169.44)).abs() < 0.001);
+        assert!((calculate_income_tax(4_000.0) - (4_000.0 * 0.225 - 662.77)).abs() < 0.001);
+        assert!((calculate_income_tax(10_000.0) - 1_854.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_rental_income() {
+        let rental = Rental {
+            enabled: true,
+            start_month: 3,
+            monthly_rent: 5_000.0,
+            yearly_adjustment: 0.1,
+            vacancy_rate: 0.2,
+            agency_fee: 0.1,
+        };
+
+        assert_eq!(calculate_rent_at(&rental, 2), 0.0);
+
+        // 5,000 * 0.8 * 0.9 = 3,600, taxed at 15%.
+        let rent = calculate_rent_at(&rental, 3);
+        assert!((rent - 3_600.0).abs() < 0.001);
+        assert!((calculate_income_tax(rent) - 158.56).abs() < 0.001);
+
+        // Adjusted by 10% after a year: 3,960, taxed at 22.5%.
+        let rent = calculate_rent_at(&rental, 15);
+        assert!((rent - 3_960.0).abs() < 0.001);
+        assert!((calculate_income_tax(rent) - 228.23).abs() < 0.001);
+
+        let house = House {
+            house_price: 600_000.0,
+            rental,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &Buyer::default(),
+            &house,
+            &simulation(24),
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[],
+        );
+        let yield_on_cost = output.rental_yield_on_cost.unwrap();
+        assert!((yield_on_cost - 3_441.44 * 12.0 / 600_000.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_calculate_capital_gains_tax() {
+        assert_eq!(calculate_capital_gains_tax(-10_000.0, 12, 0.0), 0.0);
+        assert_eq!(calculate_capital_gains_tax(100_000.0, 0, 0.0), 15_000.0);
+        assert_eq!(calculate_capital_gains_tax(100_000.0, 0, 1.0), 0.0);
+
+        let reduced_gain = 100_000.0 / 1.0035_f64.powi(84);
+        assert!(
+            (calculate_capital_gains_tax(100_000.0, 84, 0.5) - reduced_gain * 0.5 * 0.15).abs()
+                < 0.001
+        );
+
+        // 15% over the first 5M and 17.5% over the remaining 1M.
+        assert!((calculate_capital_gains_tax(6_000_000.0, 0, 0.0) - 925_000.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_sale() {
+        let house = House {
+            house_price: 120_000.0,
+            down_payment: 0.0,
+            house_monthly_interest: 0.0,
+            months_to_pay: 12,
+            monthly_appreciation: 0.01,
+            sale: Sale {
+                enabled: true,
+                month: 5,
+                broker_commission: 0.05,
+                reinvested_fraction: 0.0,
+            },
+            ..Default::default()
+        };
+        let buyer = Buyer {
+            starting_money: 0.0,
+            liquid_salary: 10_000.0,
+            fixed_monthly_expenses: 0.0,
+            investment_monthly_interest: 0.0,
+            overdraft_monthly_interest: 0.0,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &house,
+            &simulation(6),
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[],
+        );
+
+        let sale = output.loans[0].sale.as_ref().unwrap();
+        let sale_price = 120_000.0 * 1.01_f64.powi(4);
+        assert!((sale.sale_price - sale_price).abs() < 0.001);
+        assert_eq!(sale.outstanding_balance, 80_000.0);
+        assert!((sale.capital_gain - (sale_price * 0.95 - 120_000.0)).abs() < 0.001);
+        assert_eq!(sale.capital_gains_tax, 0.0);
+
+        // Four installments of 10,000 are paid, then the proceeds arrive.
+        assert_eq!(output.loans[0].monthly_payments.len(), 4);
+        assert_eq!(output.loans[0].ends_after, 4);
+        assert!((output.time_series[5] - (10_000.0 + sale.net_proceeds())).abs() < 0.001);
+        assert_eq!(output.time_series[6], output.time_series[5] + 10_000.0);
+
+        // After the sale, the net worth is just the money on account.
+        assert_eq!(output.net_worth[6], output.time_series[6]);
+        assert!(
+            (output.net_worth[4] - (0.0 + 120_000.0 * 1.01_f64.powi(4) - 80_000.0)).abs() < 0.001
+        );
+    }
+
+    #[test]
+    fn test_home_equity() {
+        let house = House {
+            house_price: 100_000.0,
+            down_payment: 0.0,
+            house_monthly_interest: 0.0,
+            months_to_pay: 10,
+            monthly_appreciation: 0.0,
+            home_equity: HomeEquity {
+                enabled: true,
+                month: 3,
+                amount: 50_000.0,
+                max_loan_to_value: 1.0,
+                monthly_interest: 0.0,
+                strategy: AmortizationStrategyType::Sac,
+                months_to_pay: 5,
+            },
+            sale: Sale {
+                enabled: true,
+                month: 5,
+                broker_commission: 0.0,
+                reinvested_fraction: 0.0,
+            },
+            ..Default::default()
+        };
+        let buyer = Buyer {
+            starting_money: 0.0,
+            liquid_salary: 20_000.0,
+            fixed_monthly_expenses: 0.0,
+            investment_monthly_interest: 0.0,
+            overdraft_monthly_interest: 0.0,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &house,
+            &simulation(5),
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[],
+        );
+
+        // Limited to the appraised value minus the 80,000 still owed.
+        let home_equity = &output.loans[1];
+        assert_eq!(home_equity.disbursement, 20_000.0);
+        assert_eq!(home_equity.monthly_payments, vec![4_000.0]);
+        assert_eq!(output.time_series[3], 50_000.0);
+        assert_eq!(
+            output.net_worth[3],
+            50_000.0 + 100_000.0 - 70_000.0 - 20_000.0
+        );
+        assert_eq!(output.time_series[4], 56_000.0);
+
+        // The sale pays off both loans.
+        let sale = output.loans[0].sale.as_ref().unwrap();
+        assert_eq!(sale.outstanding_balance, 60_000.0 + 16_000.0);
+        assert_eq!(output.time_series[5], 56_000.0 + 20_000.0 + 24_000.0);
+        assert_eq!(output.net_worth[5], output.time_series[5]);
+    }
+
+    #[test]
+    fn test_additional_loans() {
+        let car = AdditionalLoan {
+            house: House {
+                name: "Carro".to_string(),
+                house_price: 30_000.0,
+                down_payment: 6_000.0,
+                house_monthly_interest: 0.0,
+                months_to_pay: 12,
+                ..Default::default()
+            },
+            strategy: AmortizationStrategyType::Price,
+            purchased_after: 0,
+            is_property: false,
+        };
+        let apartment = AdditionalLoan {
+            house: House {
+                name: "Apartamento".to_string(),
+                house_price: 100_000.0,
+                down_payment: 10_000.0,
+                house_monthly_interest: 0.0,
+                months_to_pay: 9,
+                monthly_appreciation: 0.0,
+                ..Default::default()
+            },
+            strategy: AmortizationStrategyType::Sac,
+            purchased_after: 2,
+            is_property: true,
+        };
+        let buyer = Buyer {
+            starting_money: 50_000.0,
+            liquid_salary: 0.0,
+            fixed_monthly_expenses: 0.0,
+            investment_monthly_interest: 0.0,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &no_house(),
+            &simulation(3),
+            AmortizationStrategyType::Sac,
+            &TradeUp::default(),
+            &[car, apartment],
+        );
+
+        assert_eq!(output.loans.len(), 3);
+        assert_eq!(output.loans[1].name, "Carro");
+
+        // Installments of 2,000 for the car, and of 10,000 for the apartment
+        // bought on month 2.
+        for (money, expected) in output
+            .time_series
+            .iter()
+            .zip([44_000.0, 42_000.0, 30_000.0, 18_000.0])
+        {
+            assert!((money - expected).abs() < 0.01);
+        }
+
+        // The car is not an asset, only its debt counts.
+        assert!((output.net_worth[0] - (44_000.0 - 24_000.0)).abs() < 0.01);
+        assert!((output.net_worth[3] - (18_000.0 + 100_000.0 - 80_000.0 - 18_000.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_trade_up() {
+        let house = House {
+            house_price: 120_000.0,
+            down_payment: 0.0,
+            house_monthly_interest: 0.0,
+            months_to_pay: 12,
+            monthly_appreciation: 0.01,
+            sale: Sale {
+                enabled: true,
+                month: 5,
+                broker_commission: 0.05,
+                reinvested_fraction: 0.0,
+            },
+            ..Default::default()
+        };
+        let trade_up = TradeUp {
+            enabled: true,
+            house: House {
+                name: "Casa".to_string(),
+                house_price: 200_000.0,
+                down_payment: 5_000.0,
+                house_monthly_interest: 0.0,
+                months_to_pay: 10,
+                monthly_appreciation: 0.0,
+                ..Default::default()
+            },
+            strategy: AmortizationStrategyType::Sac,
+        };
+        let buyer = Buyer {
+            starting_money: 0.0,
+            liquid_salary: 10_000.0,
+            fixed_monthly_expenses: 0.0,
+            investment_monthly_interest: 0.0,
+            overdraft_monthly_interest: 0.0,
+            ..Default::default()
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &house,
+            &simulation(7),
+            AmortizationStrategyType::Sac,
+            &trade_up,
+            &[],
+        );
+
+        assert_eq!(output.loans.len(), 2);
+        let net_proceeds = output.loans[0].sale.as_ref().unwrap().net_proceeds();
+        let next = &output.loans[1];
+        assert_eq!(next.name, "Casa");
+        assert_eq!(next.purchased_after, 5);
+        assert!((next.down_payment - (5_000.0 + net_proceeds)).abs() < 0.001);
+
+        // The proceeds go straight into the next home, only the extra cash leaves.
+        assert!((output.time_series[5] - 5_000.0).abs() < 0.001);
+
+        let financed = 200_000.0 - next.down_payment;
+        assert_eq!(next.starts_after, 5);
+        assert!((next.monthly_payments[0] - financed / 10.0).abs() < 0.001);
+        assert!((output.time_series[6] - (15_000.0 - financed / 10.0)).abs() < 0.001);
+        assert!(
+            (output.net_worth[6] - (output.time_series[6] + 200_000.0 - financed * 0.9)).abs()
+                < 0.001
+        );
+
+        // Trading down, the proceeds above the next price stay on account,
+        // and only the part of them put into the next home is exempt.
+        let house = House {
+            monthly_appreciation: 0.05,
+            ..house
+        };
+        let trade_down = TradeUp {
+            enabled: true,
+            house: House {
+                house_price: 20_000.0,
+                ..trade_up.house.clone()
+            },
+            strategy: AmortizationStrategyType::Sac,
+        };
+        let output = calculate_money_timeseries(
+            &buyer,
+            &house,
+            &simulation(7),
+            AmortizationStrategyType::Sac,
+            &trade_down,
+            &[],
+        );
+
+        let sale = output.loans[0].sale.as_ref().unwrap();
+        let next = &output.loans[1];
+        assert_eq!(next.down_payment, 20_000.0);
+        assert!(next.monthly_payments.iter().all(|payment| *payment == 0.0));
+        assert!(sale.capital_gains_tax > 0.0);
+        let reinvested_fraction = 15_000.0 / (sale.net_proceeds() + sale.capital_gains_tax);
+        assert!(
+            (sale.capital_gains_tax
+                - calculate_capital_gains_tax(sale.capital_gain, 4, reinvested_fraction))
+            .abs()
+                < 1e-9
+        );
+
+        let left_on_account = 10_000.0 + sale.net_proceeds() - 20_000.0;
+        assert!(left_on_account > 20_000.0);
+        assert!((output.time_series[5] - left_on_account).abs() < 0.001);
+        assert!((output.net_worth[5] - (left_on_account + 20_000.0)).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_refinancing() {
+        let house = House {
+            house_price: 12_000.0,
+            down_payment: 0.0,
+            house_monthly_interest: 0.01,
+            months_to_pay: 12,
+            refinancing: Refinancing {
+                enabled: true,
+                month: 5,
+                monthly_interest: 0.0,
+                strategy: AmortizationStrategyType::Sac,
+                months_to_pay: 4,
+                switching_costs: 100.0,
+            },
+            ..Default::default()
+        };
+        let schedule = calculate_loan_schedule(&house, AmortizationStrategyType::Sac, 0);
+
+        // 4 installments of 1,000 plus interest, then 8,000 are paid in 4
+        // installments without interest.
+        assert_eq!(schedule.monthly_payments.len(), 8);
+        assert!((schedule.monthly_payments[3] - 1_090.0).abs() < 0.001);
+        assert_eq!(schedule.monthly_payments[4..], [2_000.0; 4]);
+        assert_eq!(schedule.ends_after, 8);
+        assert_eq!(schedule.balances.last(), Some(&0.0));
+        assert_eq!(schedule.refinancing_costs_at(4), 100.0);
+
+        let report = schedule.refinancing.unwrap();
+        assert_eq!(report.month, 5);
+        assert!((report.original_payments[0] - 1_080.0).abs() < 0.001);
+        assert_eq!(report.new_first_payment, 2_000.0);
+
+        // The original loan would still charge 80 + 70 + ... + 10 of interest.
+        assert!((report.net_savings() - (360.0 - 100.0)).abs() < 0.001);
+
+        // Refinancing at the same rate and term, without costs, saves nothing,
+        // as the intermediate payments and the final balloon are kept.
+        for strategy in [
+            AmortizationStrategyType::Sac,
+            AmortizationStrategyType::Price,
+        ] {
+            let house = House {
+                intermediate_payments: IntermediatePayments {
+                    enabled: true,
+                    amount: 1_000.0,
+                    months_between: 3,
+                    final_balloon: 2_000.0,
+                },
+                refinancing: Refinancing {
+                    monthly_interest: 0.01,
+                    strategy,
+                    months_to_pay: 8,
+                    switching_costs: 0.0,
+                    ..house.refinancing.clone()
+                },
+                ..house.clone()
+            };
+            let schedule = calculate_loan_schedule(&house, strategy, 0);
+            let report = schedule.refinancing.as_ref().unwrap();
+
+            assert_eq!(schedule.balloon_payments[5], 1_000.0);
+            assert_eq!(schedule.balloon_payments[11], 3_000.0);
+            assert!(report.net_savings().abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_grace_period() {
+        let mut grace_period = GracePeriod {
+            enabled: true,
+            months: 2,
+            mode: GracePeriodMode::InterestOnly,
+        };
+
+        let schedule = calculate_amortization_after_grace(
+            100_000.0,
+            0.01,
+            12,
+            0.0,
+            AmortizationStrategyType::Sac,
+            &grace_period,
+            &[],
+            12,
+        );
+        assert_eq!(schedule.monthly_payments.len(), 12);
+        assert_eq!(schedule.ends_after, 12);
+        assert_eq!(schedule.monthly_payments[..3], [1_000.0, 1_000.0, 11_000.0]);
+        assert_eq!(
+            schedule.balances[..4],
+            [100_000.0, 100_000.0, 100_000.0, 90_000.0]
+        );
+
+        grace_period.mode = GracePeriodMode::Capitalized;
+        let schedule = calculate_amortization_after_grace(
+            100_000.0,
+            0.01,
+            12,
+            0.0,
+            AmortizationStrategyType::Sac,
+            &grace_period,
+            &[],
+            12,
+        );
+        let capitalized = 100_000.0 * 1.01 * 1.01;
+        assert_eq!(schedule.monthly_payments[..2], [0.0, 0.0]);
+        assert!((schedule.balances[2] - capitalized).abs() < 0.001);
+        assert!(
+            (schedule.monthly_payments[2] - (capitalized / 10.0 + capitalized * 0.01)).abs()
+                < 0.001
+        );
+        assert_eq!(*schedule.balances.last().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_balloon_payments() {
+        let intermediate_payments = IntermediatePayments {
+            enabled: true,
+            amount: 1_000.0,
+            months_between: 6,
+            final_balloon: 2_000.0,
+        };
+        let balloons = calculate_balloon_schedule(&intermediate_payments, 12);
+        assert_eq!(balloons[5], 1_000.0);
+        assert_eq!(balloons[11], 3_000.0);
+        assert_eq!(balloons.iter().sum::<f64>(), 4_000.0);
+
+        // The regular amortization covers what the balloons leave.
+        let schedule = calculate_loan_schedule_sac(12_000.0, 0.01, 12, 0.0, &balloons, 12);
+        assert!((schedule.monthly_payments[0] - (120.0 + 8_000.0 / 12.0)).abs() < 0.001);
+        assert_eq!(schedule.balloon_payments[5], 1_000.0);
+        assert!((schedule.balloon_payments[11] - 3_000.0).abs() < 0.001);
+        assert!(*schedule.balances.last().unwrap() < 0.001);
+
+        // The installment finances only the present value of the balloon.
+        let mut balloons = vec![0.0; 12];
+        balloons[11] = 5_000.0;
+        let schedule = calculate_loan_schedule_price(10_000.0, 0.01, 12, &balloons);
+        let financed = 10_000.0 - 5_000.0 / 1.01_f64.powi(12);
+        let payment = financed * 0.01 / (1.0 - 1.01_f64.powi(-12));
+        assert!((schedule.monthly_payments[0] - payment).abs() < 0.01);
+        assert!((schedule.balloon_payments[11] - 5_000.0).abs() < 0.01);
+        assert!(*schedule.balances.last().unwrap() < 0.01);
+    }
+
+    #[test]
+    fn test_round_to_centavos() {
+        assert_eq!(round_to_centavos(0.125, RoundingMode::HalfUp), 0.13);
+        assert_eq!(round_to_centavos(0.125, RoundingMode::HalfEven), 0.12);
+        assert_eq!(round_to_centavos(0.135, RoundingMode::HalfEven), 0.14);
+        assert_eq!(round_to_centavos(2.675, RoundingMode::HalfUp), 2.68);
+        assert_eq!(round_to_centavos(2.675, RoundingMode::None), 2.675);
+    }
+
+    #[test]
+    fn test_rounded_schedules() {
+        // PRICE statement of 10,000.00 at 1% a month in 12 installments.
+        let schedule = round_schedule(
+            calculate_loan_schedule_price(10_000.0, 0.01, 12, &[]),
+            0.01,
+            AmortizationStrategyType::Price,
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(schedule.monthly_payments[..11], [888.49; 11]);
+        assert_eq!(schedule.monthly_payments[11], 888.47);
+        assert_eq!(
+            schedule.balances[..4],
+            [10_000.0, 9_211.51, 8_415.14, 7_610.8]
+        );
+        assert_eq!(schedule.balances[11], 879.67);
+        assert_eq!(schedule.balances[12], 0.0);
+
+        // SAC statement of 10,000.00 at 0.75% a month in 12 installments.
+        let schedule = round_schedule(
+            calculate_loan_schedule_sac(10_000.0, 0.0075, 12, 0.0, &[], 12),
+            0.0075,
+            AmortizationStrategyType::Sac,
+            RoundingMode::HalfEven,
+        );
+        assert_eq!(schedule.monthly_payments[..3], [908.33, 902.08, 895.83]);
+        assert_eq!(schedule.balances[1], 9_166.67);
+        assert_eq!(schedule.balances[11], 833.37);
+        assert_eq!(schedule.monthly_payments[11], 839.62);
+        assert_eq!(schedule.balances[12], 0.0);
+    }
+
+    #[test]
+    fn test_loan_fees() {
+        let house = House {
+            house_price: 110_000.0,
+            down_payment: 10_000.0,
+            house_monthly_interest: 0.01,
+            months_to_pay: 120,
+            ..Default::default()
+        };
+
+        let loan = calculate_loan_schedule(&house, AmortizationStrategyType::Price, 0);
+        let expected = 1.01_f64.powi(12) - 1.0;
+        assert!((loan.effective_cost().unwrap() - expected).abs() < 1e-6);
+        assert!(loan.charges.iter().all(|charge| *charge == 0.0));
+
+        let with_fees = House {
+            fees: LoanFees {
+                upfront: 2_000.0,
+                monthly_fee: 25.0,
+                monthly_insurance_rate: 0.0003,
+                ..LoanFees::default()
+            },
+            ..house
+        };
+        let loan_with_fees =
+            calculate_loan_schedule(&with_fees, AmortizationStrategyType::Price, 0);
+        let bought_later = calculate_loan_schedule(&with_fees, AmortizationStrategyType::Price, 3);
+
+        // The insurance follows the balance.
+        assert!((loan_with_fees.charges[0] - 55.0).abs() < 1e-9);
+        assert!(loan_with_fees.charges[119] < loan_with_fees.charges[0]);
+        assert_eq!(bought_later.down_payment_at(2), 12_000.0);
+        assert!(
+            (loan_with_fees.total_paid()
+                - loan.total_paid()
+                - 2_000.0
+                - loan_with_fees.charges.iter().sum::<f64>())
+            .abs()
+                < 1e-6
+        );
+        assert!(loan_with_fees.effective_cost().unwrap() > expected + 0.01);
+    }
+
+    #[test]
+    fn test_pmi_and_escrow() {
+        let house = House {
+            house_price: 100_000.0,
+            down_payment: 10_000.0,
+            house_monthly_interest: 0.0,
+            months_to_pay: 90,
+            monthly_appreciation: 0.0,
+            fees: LoanFees {
+                pmi_monthly_rate: 0.0005,
+                monthly_property_tax_rate: 0.001,
+                monthly_homeowners_insurance: 50.0,
+                ..LoanFees::default()
+            },
+            ..Default::default()
+        };
+        let loan = calculate_loan_schedule(&house, AmortizationStrategyType::Sac, 0);
+
+        // 1,000 is amortized a month, so the balance reaches 80% of the value
+        // before the 11th installment.
+        assert_eq!(loan.pmi_drops_at, Some(11));
+        assert!((loan.charges[9] - 45.0).abs() < 1e-9);
+        assert_eq!(loan.charges[10], 0.0);
+        assert!((loan.escrow_at(89) - 150.0).abs() < 1e-9);
+        assert_eq!(loan.escrow_at(90), 0.0);
+
+        // Appreciation lowers the loan-to-value sooner.
+        let appreciating = House {
+            monthly_appreciation: 0.01,
+            ..house
+        };
+        let loan = calculate_loan_schedule(&appreciating, AmortizationStrategyType::Sac, 0);
+        assert_eq!(loan.pmi_drops_at, Some(7));
+        assert!(loan.escrow[1] > loan.escrow[0]);
+    }
+
+    #[test]
+    fn test_payment_frequency() {
+        assert_eq!(
+            convert_monthly_interest(0.01, PaymentFrequency::Monthly, DayCount::Business252),
+            0.01
+        );
+
+        // A week is 7 of 360 days, or 5 of 252 business days.
+        let weekly = convert_monthly_interest(0.01, PaymentFrequency::Weekly, DayCount::Thirty360);
+        assert!((weekly - (1.01_f64.powf(12.0 * 7.0 / 360.0) - 1.0)).abs() < 1e-15);
+        let weekly =
+            convert_monthly_interest(0.01, PaymentFrequency::Weekly, DayCount::Business252);
+        assert!((weekly - (1.01_f64.powf(12.0 * 5.0 / 252.0) - 1.0)).abs() < 1e-15);
+
+        // Two weeks accrue less interest over 365 days than over 360.
+        assert!(
+            convert_monthly_interest(0.01, PaymentFrequency::Biweekly, DayCount::Actual365)
+                < convert_monthly_interest(0.01, PaymentFrequency::Biweekly, DayCount::Thirty360)
+        );
+
+        let house = House {
+            house_price: 130_000.0,
+            down_payment: 0.0,
+            months_to_pay: 24,
+            yearly_extra_amortization: 10_000.0,
+            payment_frequency: PaymentFrequency::Biweekly,
+            ..Default::default()
+        };
+        let loan = calculate_loan_schedule(&house, AmortizationStrategyType::Sac, 0);
+
+        // 52 installments of 2,500 of amortization, two or three a month,
+        // with the extra amortization on the 26th, which ends the loan four
+        // installments earlier.
+        assert!((loan.balances[1] - 125_000.0).abs() < 1e-6);
+        assert!((loan.balances[6] - 97_500.0).abs() < 1e-6);
+        assert_eq!(loan.extra_amortizations[11], 10_000.0);
+        assert_eq!(loan.ends_after, 23);
+        assert_eq!(loan.monthly_payments.len(), 23);
+        assert!(loan.balances[23].abs() < 1e-6);
+
+        let period_interest =
+            convert_monthly_interest(0.01, PaymentFrequency::Biweekly, DayCount::Thirty360);
+        let first_two = 2_500.0 * 2.0 + (130_000.0 + 127_500.0) * period_interest;
+        assert!((loan.monthly_payments[0] - first_two).abs() < 1e-6);
+
+        // Nothing is financed when the down payment pays the whole house.
+        for payment_frequency in [PaymentFrequency::Biweekly, PaymentFrequency::Weekly] {
+            for strategy in [
+                AmortizationStrategyType::Sac,
+                AmortizationStrategyType::Price,
+            ] {
+                let house = House {
+                    down_payment: house.house_price,
+                    payment_frequency,
+                    ..house.clone()
+                };
+                let loan = calculate_loan_schedule(&house, strategy, 0);
+
+                assert_eq!(loan.ends_after, 0);
+                assert!(loan.monthly_payments.iter().all(|payment| *payment == 0.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_adjustable_rate() {
+        let house = House {
+            house_price: 100_000.0,
+            down_payment: 0.0,
+            house_monthly_interest: 0.01,
+            months_to_pay: 120,
+            adjustable_rate: AdjustableRate {
+                enabled: true,
+                fixed_months: 12,
+                reset_months: 12,
+                index_path: vec![0.012, 0.02],
+                margin: 0.002,
+                periodic_cap: 0.003,
+                lifetime_cap: 0.005,
+                floor: 0.0,
+            },
+            ..Default::default()
+        };
+        let loan = calculate_loan_schedule(&house, AmortizationStrategyType::Price, 0);
+
+        // 1.4% is held to 1.3% by the periodic cap, and 2.2% to 1.5% by the
+        // lifetime cap.
+        assert_eq!(loan.rate_resets.len(), 9);
+        assert_eq!(loan.rate_resets[0].month, 13);
+        assert!((loan.rate_resets[0].monthly_interest - 0.013).abs() < 1e-12);
+        assert!((loan.rate_resets[1].monthly_interest - 0.015).abs() < 1e-12);
+        assert!((loan.rate_resets[8].monthly_interest - 0.015).abs() < 1e-12);
+
+        let annuity =
+            |balance: f64, rate: f64, n: i32| balance * rate / (1.0 - (1.0 + rate).powi(-n));
+        assert!((loan.monthly_payments[0] - annuity(100_000.0, 0.01, 120)).abs() < 0.01);
+        assert!((loan.monthly_payments[12] - annuity(loan.balances[12], 0.013, 108)).abs() < 0.01);
+        assert_eq!(loan.rate_resets[0].first_payment, loan.monthly_payments[12]);
+        assert_eq!(loan.monthly_payments.len(), 120);
+        assert!(loan.balances[120].abs() < 0.01);
+
+        // SAC keeps amortizing the same, with the interest over the new rate.
+        let loan = calculate_loan_schedule(
+            &House {
+                adjustable_rate: AdjustableRate {
+                    index_path: vec![-0.01],
+                    periodic_cap: 1.0,
+                    floor: 0.005,
+                    ..house.adjustable_rate.clone()
+                },
+                ..house.clone()
+            },
+            AmortizationStrategyType::Sac,
+            0,
+        );
+        assert!((loan.rate_resets[0].monthly_interest - 0.005).abs() < 1e-12);
+        assert!((loan.monthly_payments[12] - (90_000.0 * 0.005 + 833.333_333)).abs() < 1e-3);
+
+        // Resetting every six months to the same rate keeps the yearly extra
+        // amortizations, the grace period and the intermediate payments where
+        // the fixed rate has them.
+        let fixed = House {
+            yearly_extra_amortization: 5_000.0,
+            grace_period: GracePeriod {
+                enabled: true,
+                months: 9,
+                mode: GracePeriodMode::Capitalized,
+            },
+            intermediate_payments: IntermediatePayments {
+                enabled: true,
+                amount: 2_000.0,
+                months_between: 12,
+                final_balloon: 0.0,
+            },
+            adjustable_rate: AdjustableRate::default(),
+            ..house.clone()
+        };
+        let adjustable = House {
+            adjustable_rate: AdjustableRate {
+                enabled: true,
+                fixed_months: 6,
+                reset_months: 6,
+                index_path: vec![0.01],
+                margin: 0.0,
+                periodic_cap: 1.0,
+                lifetime_cap: 1.0,
+                floor: 0.0,
+            },
+            ..fixed.clone()
+        };
+        for strategy in [
+            AmortizationStrategyType::Sac,
+            AmortizationStrategyType::Price,
+        ] {
+            let fixed = calculate_loan_schedule(&fixed, strategy, 0);
+            let adjustable = calculate_loan_schedule(&adjustable, strategy, 0);
+
+            assert_eq!(adjustable.rate_resets[0].month, 7);
+            assert_eq!(adjustable.rate_resets[1].month, 13);
+            assert_eq!(adjustable.ends_after, fixed.ends_after);
+            assert_eq!(adjustable.extra_amortizations, fixed.extra_amortizations);
+            assert_eq!(adjustable.balloon_payments, fixed.balloon_payments);
+            // PRICE installments are found by a binary search.
+            for (adjustable, fixed) in adjustable
+                .monthly_payments
+                .iter()
+                .zip(&fixed.monthly_payments)
+            {
+                assert!((adjustable - fixed).abs() < 1e-3);
+            }
+        }
+        let sac = calculate_loan_schedule(&adjustable, AmortizationStrategyType::Sac, 0);
+        assert_eq!(sac.extra_amortizations[20], 5_000.0);
+        assert!(sac.ends_after < 120);
+
+        // Nothing is financed when the down payment pays the whole house.
+        for strategy in [
+            AmortizationStrategyType::Sac,
+            AmortizationStrategyType::Price,
+        ] {
+            let house = House {
+                down_payment: adjustable.house_price,
+                ..adjustable.clone()
+            };
+            let loan = calculate_loan_schedule(&house, strategy, 0);
+
+            assert_eq!(loan.ends_after, 0);
+            assert!(loan.rate_resets.is_empty());
+            assert!(loan.monthly_payments.iter().all(|payment| *payment == 0.0));
+        }
+    }
+
+    #[test]
+    fn test_inverse_solvers() {
+        let price = AmortizationStrategyType::Price;
+        let sac = AmortizationStrategyType::Sac;
+
+        // 10,000 at 1% a month in 12 months is paid with 888.49.
+        assert_eq!(solve_term(10_000.0, 0.01, 888.49, price, 360), Some(12));
+        assert_eq!(solve_term(10_000.0, 0.01, 888.0, price, 360), Some(13));
+        assert_eq!(solve_term(10_000.0, 0.01, 100.0, price, 360), None);
+        assert!((solve_rate(10_000.0, 12, 888.4879, price).unwrap() - 0.01).abs() < 1e-6);
+        assert!((solve_value(0.01, 12, 888.4879, price) - 10_000.0).abs() < 0.01);
+
+        // The first SAC installment is 1,000 of amortization plus 100 of interest.
+        assert_eq!(solve_term(10_000.0, 0.01, 1_100.0, sac, 360), Some(10));
+        assert!((solve_rate(10_000.0, 10, 1_100.0, sac).unwrap() - 0.01).abs() < 1e-12);
+        assert!((solve_value(0.01, 10, 1_100.0, sac) - 10_000.0).abs() < 1e-9);
+
+        assert_eq!(solve_rate(10_000.0, 10, 900.0, sac), None);
+    }
+
+    #[test]
+    fn test_calculate_loan_schedule_price_balances() {
+        let schedule = calculate_loan_schedule_price(1_000.0, 0.01, 4, &[]);
+
+        // 1,010.00 - 256.28 = 753.72
+        assert!((schedule.balances[1] - 753.72).abs() < 0.01);
+        assert!(schedule.balances[4].abs() < 0.01);
+    }
+
+    #[test]
+    fn test_calculate_loan_schedule_sac_with_extra_amortization() {
+        let schedule = calculate_loan_schedule_sac(24_000.0, 0.0, 24, 6_000.0, &[], 12);
+
+        // 1,000 a month, and 6,000 extra at month 12 shorten the loan by 6 months.
+        assert_eq!(schedule.ends_after, 18);
+        assert_eq!(schedule.monthly_payments, vec![1_000.0; 18]);
+        assert_eq!(schedule.extra_amortizations[11], 6_000.0);
+    }
+
     #[test]
     fn test_calculate_monthly_payment() {
         // Tests are based on the outputs of the following tool:
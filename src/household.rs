@@ -0,0 +1,543 @@
+use crate::{
+    calculation::{calculate_money_timeseries, AmortizationStrategyType, SimulationOutput},
+    model::{
+        AccountMode, AdditionalLoan, Buyer, CashFlowFrequency, CashFlowItem, House, Indexation,
+        Simulation, TradeUp,
+    },
+};
+
+/// Monthly `MIP` rate over the balance, by the greatest age of each bracket.
+const INSURANCE_BY_AGE: [(i32, f64); 10] = [
+    (30, 0.000_10),
+    (35, 0.000_13),
+    (40, 0.000_17),
+    (45, 0.000_26),
+    (50, 0.000_40),
+    (55, 0.000_60),
+    (60, 0.001_10),
+    (65, 0.001_80),
+    (70, 0.002_70),
+    (i32::MAX, 0.004_10),
+];
+
+pub(crate) fn insurance_rate_for_age(age: i32) -> f64 {
+    INSURANCE_BY_AGE
+        .iter()
+        .find(|(max_age, _)| age <= *max_age)
+        .map_or(0.0, |(_, rate)| *rate)
+}
+
+pub(crate) fn gross_income(buyers: &[&Buyer]) -> f64 {
+    buyers.iter().map(|buyer| buyer.gross_salary).sum()
+}
+
+/// Gets the `MIP` rate of the buyers, each insured over the share of the
+/// balance their gross income composes.
+pub(crate) fn insurance_rate(buyers: &[&Buyer]) -> f64 {
+    let total_income = gross_income(buyers);
+
+    buyers
+        .iter()
+        .map(|buyer| {
+            let share = if total_income > 0.0 {
+                buyer.gross_salary / total_income
+            } else {
+                1.0 / buyers.len() as f64
+            };
+
+            insurance_rate_for_age(buyer.age) * share
+        })
+        .sum()
+}
+
+/// Charges the buyers' `MIP` rate, if the insurance follows their ages.
+pub(crate) fn apply_insurance(buyers: &[&Buyer], house: &House) -> House {
+    let mut house = house.clone();
+
+    if house.fees.insurance_by_age {
+        house.fees.monthly_insurance_rate = insurance_rate(buyers);
+    }
+
+    house
+}
+
+/// Merges the buyers into one, who keeps all the money in a single account.
+///
+/// The account is the first buyer's, with the investment rate weighted by
+/// each one's money, and so is the retirement: the savings are only checked
+/// against the first buyer's target age. The bonuses and 13th salaries of the
+/// others become yearly cash-flow items, so they still fall on their own
+/// months, and their salaries and `FGTS` deposits become cash-flow items
+/// which stop once they retire, when their pensions start.
+pub(crate) fn combine(buyers: &[&Buyer], simulation: &Simulation) -> Buyer {
+    let Some((first, others)) = buyers.split_first() else {
+        return Buyer::default();
+    };
+
+    let mut combined = Buyer {
+        installment_share: 1.0,
+        ..(*first).clone()
+    };
+
+    if others.is_empty() {
+        return combined;
+    }
+
+    let starting_money: f64 = buyers.iter().map(|buyer| buyer.starting_money).sum();
+
+    if starting_money > 0.0 {
+        combined.investment_monthly_interest = buyers
+            .iter()
+            .map(|buyer| buyer.investment_monthly_interest * buyer.starting_money)
+            .sum::<f64>()
+            / starting_money;
+    }
+
+    let income = |name: String, amount: f64, frequency: CashFlowFrequency| CashFlowItem {
+        name,
+        amount,
+        is_income: true,
+        start_month: 1,
+        end_month: None,
+        frequency,
+        indexation: Indexation::None,
+        custom_monthly_rate: 0.0,
+    };
+    let yearly_income = |name: String, amount: f64, calendar_month: i32| CashFlowItem {
+        start_month: (calendar_month - simulation.start_month).rem_euclid(12) + 1,
+        ..income(name, amount, CashFlowFrequency::Yearly)
+    };
+
+    for buyer in others {
+        combined.name = format!("{} e {}", combined.name, buyer.name);
+        combined.starting_money += buyer.starting_money;
+        combined.gross_salary += buyer.gross_salary;
+        combined.fixed_monthly_expenses += buyer.fixed_monthly_expenses;
+        combined.fgts_balance += buyer.fgts_balance;
+        combined
+            .fgts_deposits
+            .extend(buyer.fgts_deposits.iter().cloned());
+        combined.cash_flows.extend(buyer.cash_flows.iter().cloned());
+        combined
+            .expense_categories
+            .extend(buyer.expense_categories.iter().cloned());
+
+        // Last month with the salary, if the buyer retires.
+        let last_salary = buyer
+            .retirement
+            .enabled
+            .then(|| buyer.retirement.months_until(buyer.age));
+
+        let Some(last_salary) = last_salary else {
+            combined.liquid_salary += buyer.liquid_salary;
+            combined.fgts_monthly_deposit += buyer.fgts_monthly_deposit;
+
+            if buyer.yearly_bonus > 0.0 {
+                combined.cash_flows.push(yearly_income(
+                    format!("Bônus ({})", buyer.name),
+                    buyer.yearly_bonus,
+                    buyer.bonus_month,
+                ));
+            }
+
+            if buyer.thirteenth_salary {
+                for calendar_month in [11, 12] {
+                    combined.cash_flows.push(yearly_income(
+                        format!("13º ({})", buyer.name),
+                        buyer.liquid_salary / 2.0,
+                        calendar_month,
+                    ));
+                }
+            }
+
+            continue;
+        };
+
+        let until_retirement = |item: CashFlowItem| CashFlowItem {
+            end_month: Some(last_salary),
+            ..item
+        };
+        let after_retirement = |item: CashFlowItem| CashFlowItem {
+            // The first month of the item on or after retiring.
+            start_month: item.start_month
+                + ((last_salary + 1 - item.start_month).max(0) + 11) / 12 * 12,
+            indexation: Indexation::Inflation,
+            ..item
+        };
+        let pension = buyer.retirement.monthly_pension;
+
+        combined.cash_flows.push(until_retirement(income(
+            format!("Salário ({})", buyer.name),
+            buyer.liquid_salary,
+            CashFlowFrequency::Monthly,
+        )));
+        combined.fgts_deposits.push(until_retirement(income(
+            format!("FGTS ({})", buyer.name),
+            buyer.fgts_monthly_deposit,
+            CashFlowFrequency::Monthly,
+        )));
+        combined.cash_flows.push(CashFlowItem {
+            start_month: last_salary + 1,
+            indexation: Indexation::Inflation,
+            ..income(
+                format!("Aposentadoria ({})", buyer.name),
+                pension,
+                CashFlowFrequency::Monthly,
+            )
+        });
+
+        if buyer.yearly_bonus > 0.0 {
+            combined.cash_flows.push(until_retirement(yearly_income(
+                format!("Bônus ({})", buyer.name),
+                buyer.yearly_bonus,
+                buyer.bonus_month,
+            )));
+        }
+
+        if buyer.thirteenth_salary {
+            for calendar_month in [11, 12] {
+                combined.cash_flows.push(until_retirement(yearly_income(
+                    format!("13º ({})", buyer.name),
+                    buyer.liquid_salary / 2.0,
+                    calendar_month,
+                )));
+                combined.cash_flows.push(after_retirement(yearly_income(
+                    format!("13º da aposentadoria ({})", buyer.name),
+                    pension / 2.0,
+                    calendar_month,
+                )));
+            }
+        }
+    }
+
+    combined
+}
+
+/// Gets the money of the buyers, either in a single account or in one
+/// account for each, who pay their `installment_share` of the houses.
+///
+/// With separate accounts, the outputs of the buyers are added up and the
+/// money of each account is kept in `accounts`.
+pub(crate) fn calculate_household_timeseries(
+    buyers: &[&Buyer],
+    accounts: AccountMode,
+    house: &House,
+    simulation: &Simulation,
+    strategy: AmortizationStrategyType,
+    trade_up: &TradeUp,
+    additional_loans: &[AdditionalLoan],
+) -> SimulationOutput {
+    let simulate = |buyer: &Buyer| {
+        calculate_money_timeseries(
+            buyer,
+            house,
+            simulation,
+            strategy,
+            trade_up,
+            additional_loans,
+        )
+    };
+
+    if accounts == AccountMode::Combined || buyers.len() < 2 {
+        let mut output = simulate(&combine(buyers, simulation));
+
+        // The account, and its retirement, are the first buyer's.
+        if let Some(first) = buyers.first() {
+            for retirement in &mut output.retirements {
+                retirement.name = first.name.clone();
+            }
+        }
+
+        return output;
+    }
+
+    let total_share: f64 = buyers.iter().map(|buyer| buyer.installment_share).sum();
+
+    buyers
+        .iter()
+        .map(|buyer| {
+            let share = if total_share > 0.0 {
+                buyer.installment_share / total_share
+            } else {
+                1.0 / buyers.len() as f64
+            };
+
+            let mut output = simulate(&Buyer {
+                installment_share: share,
+                ..(*buyer).clone()
+            });
+            output.accounts = vec![(buyer.name.clone(), output.time_series.clone())];
+
+            for (name, _) in output
+                .cash_flow_breakdown
+                .iter_mut()
+                .chain(&mut output.expense_breakdown)
+            {
+                *name = format!("{} ({})", name, buyer.name);
+            }
+
+            output
+        })
+        .reduce(add_outputs)
+        .expect("There are at least two buyers")
+}
+
+/// Adds up the outputs of two buyers, keeping the loans of the first.
+fn add_outputs(total: SimulationOutput, other: SimulationOutput) -> SimulationOutput {
+    let add =
+        |a: Vec<f64>, b: Vec<f64>| -> Vec<f64> { a.iter().zip(&b).map(|(a, b)| a + b).collect() };
+
+    SimulationOutput {
+        time_series: add(total.time_series, other.time_series),
+        net_worth: add(total.net_worth, other.net_worth),
+        loans: total.loans,
+        first_negative_month: match (total.first_negative_month, other.first_negative_month) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        },
+        monthly_credit_interest: add(total.monthly_credit_interest, other.monthly_credit_interest),
+        monthly_outflows: add(total.monthly_outflows, other.monthly_outflows),
+        cash_flow_breakdown: [total.cash_flow_breakdown, other.cash_flow_breakdown].concat(),
+        general_expenses: add(total.general_expenses, other.general_expenses),
+        expense_breakdown: [total.expense_breakdown, other.expense_breakdown].concat(),
+        rental_income: add(total.rental_income, other.rental_income),
+        rental_taxes: add(total.rental_taxes, other.rental_taxes),
+        // Each buyer's yield is over their share of the rent.
+        rental_yield_on_cost: match (total.rental_yield_on_cost, other.rental_yield_on_cost) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        },
+        accounts: [total.accounts, other.accounts].concat(),
+        retirements: [total.retirements, other.retirements].concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{LoanFees, Offer, Retirement};
+
+    fn buyer(name: &str, starting_money: f64, liquid_salary: f64) -> Buyer {
+        Buyer {
+            name: name.to_string(),
+            starting_money,
+            liquid_salary,
+            fixed_monthly_expenses: 1_000.0,
+            investment_monthly_interest: 0.0,
+            overdraft_monthly_interest: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_insurance_rate() {
+        assert_eq!(insurance_rate_for_age(25), 0.000_10);
+        assert_eq!(insurance_rate_for_age(62), 0.001_80);
+        assert_eq!(insurance_rate_for_age(90), 0.004_10);
+
+        // Three quarters of the income are of the younger buyer.
+        let younger = Buyer {
+            age: 30,
+            gross_salary: 9_000.0,
+            ..Default::default()
+        };
+        let older = Buyer {
+            age: 58,
+            gross_salary: 3_000.0,
+            ..Default::default()
+        };
+        let rate = insurance_rate(&[&younger, &older]);
+        assert!((rate - (0.75 * 0.000_10 + 0.25 * 0.001_10)).abs() < 1e-12);
+        assert_eq!(gross_income(&[&younger, &older]), 12_000.0);
+
+        // An offer brings its own fees, but the insurance still follows the
+        // buyers' ages.
+        let house = House {
+            fees: LoanFees {
+                insurance_by_age: true,
+                pmi_monthly_rate: 0.0005,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let offer = Offer::default();
+        let offered = apply_insurance(&[&younger, &older], &offer.apply_to(&house));
+        assert_eq!(offered.fees.upfront, offer.fees.upfront);
+        assert_eq!(offered.fees.pmi_monthly_rate, 0.0005);
+        assert!((offered.fees.monthly_insurance_rate - rate).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_accounts() {
+        let house = House {
+            house_price: 100_000.0,
+            down_payment: 20_000.0,
+            house_monthly_interest: 0.0,
+            months_to_pay: 80,
+            monthly_appreciation: 0.0,
+            ..Default::default()
+        };
+        let simulation = Simulation {
+            months_to_forecast: 12,
+            inflation: 0.0,
+            start_month: 3,
+            ..Default::default()
+        };
+        let first = Buyer {
+            installment_share: 3.0,
+            ..buyer("Ana", 50_000.0, 5_000.0)
+        };
+        let second = Buyer {
+            yearly_bonus: 2_000.0,
+            bonus_month: 6,
+            installment_share: 1.0,
+            ..buyer("Bia", 10_000.0, 3_000.0)
+        };
+        let buyers = [&first, &second];
+
+        let simulate = |accounts: AccountMode| {
+            calculate_household_timeseries(
+                &buyers,
+                accounts,
+                &house,
+                &simulation,
+                AmortizationStrategyType::Sac,
+                &TradeUp::default(),
+                &[],
+            )
+        };
+
+        // 60,000 - 20,000, then 8,000 - 2,000 - 1,000 a month, and Bia's
+        // bonus in June, the 4th month.
+        let combined = simulate(AccountMode::Combined);
+        assert!(combined.accounts.is_empty());
+        assert!((combined.time_series[3] - 55_000.0).abs() < 1e-9);
+        assert!((combined.time_series[4] - 62_000.0).abs() < 1e-9);
+        assert_eq!(combined.net_worth[0], 40_000.0 + 20_000.0);
+
+        // Ana pays three quarters of the house, and Bia the rest.
+        let separate = simulate(AccountMode::Separate);
+        assert_eq!(separate.accounts.len(), 2);
+        assert_eq!(separate.accounts[0].0, "Ana");
+        assert!((separate.accounts[0].1[1] - (35_000.0 + 5_000.0 - 1_000.0 - 750.0)).abs() < 1e-9);
+        assert!((separate.accounts[1].1[1] - (5_000.0 + 3_000.0 - 1_000.0 - 250.0)).abs() < 1e-9);
+        assert!((separate.time_series[4] - combined.time_series[4]).abs() < 1e-9);
+        assert!((separate.net_worth[12] - combined.net_worth[12]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_breakdown_labels() {
+        let house = House {
+            house_price: 0.0,
+            down_payment: 0.0,
+            ..Default::default()
+        };
+        let simulation = Simulation {
+            months_to_forecast: 24,
+            inflation: 0.0,
+            ..Default::default()
+        };
+        let retirement = Retirement {
+            enabled: true,
+            age: 36,
+            ..Default::default()
+        };
+        let first = Buyer {
+            cash_flows: vec![CashFlowItem {
+                name: "Carro".to_string(),
+                amount: 500.0,
+                ..Default::default()
+            }],
+            retirement: retirement.clone(),
+            ..buyer("Ana", 50_000.0, 5_000.0)
+        };
+        let second = Buyer {
+            yearly_bonus: 2_000.0,
+            thirteenth_salary: true,
+            cash_flows: vec![CashFlowItem {
+                name: "Curso".to_string(),
+                amount: 300.0,
+                ..Default::default()
+            }],
+            retirement,
+            ..buyer("Bia", 10_000.0, 3_000.0)
+        };
+        let third = buyer("Caio", 0.0, 2_000.0);
+        let buyers = [&first, &second, &third];
+
+        let simulate = |accounts: AccountMode| {
+            calculate_household_timeseries(
+                &buyers,
+                accounts,
+                &house,
+                &simulation,
+                AmortizationStrategyType::Sac,
+                &TradeUp::default(),
+                &[],
+            )
+        };
+        let names = |output: &SimulationOutput| -> Vec<String> {
+            output
+                .cash_flow_breakdown
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect()
+        };
+
+        // Each series keeps its own name, whatever is added between buyers.
+        let combined = simulate(AccountMode::Combined);
+        let carro = &combined.cash_flow_breakdown[0];
+        assert_eq!(carro.0, "Carro");
+        assert_eq!(carro.1[0], -500.0);
+        let curso = combined
+            .cash_flow_breakdown
+            .iter()
+            .find(|(name, _)| name == "Curso")
+            .unwrap();
+        assert_eq!(curso.1[0], -300.0);
+        assert!(names(&combined).contains(&"Bônus (Bia)".to_string()));
+        assert_eq!(combined.retirements.len(), 1);
+        assert_eq!(combined.retirements[0].name, "Ana");
+
+        // Bia's deposits into the FGTS stop when she retires, after a year.
+        let depositing = |buyer: &Buyer| Buyer {
+            fgts_monthly_deposit: 100.0,
+            ..buyer.clone()
+        };
+        let combined_buyer = combine(&[&depositing(&first), &depositing(&second)], &simulation);
+        assert_eq!(combined_buyer.fgts_monthly_deposit, 100.0);
+        assert_eq!(combined_buyer.fgts_deposits.len(), 1);
+        assert_eq!(combined_buyer.fgts_deposits[0].end_month, Some(12));
+
+        let net_worth = |second: &Buyer| {
+            calculate_household_timeseries(
+                &[&depositing(&first), second],
+                AccountMode::Combined,
+                &house,
+                &simulation,
+                AmortizationStrategyType::Sac,
+                &TradeUp::default(),
+                &[],
+            )
+            .net_worth[24]
+        };
+        let yearly_deposits: f64 = (0..12).map(|k| 100.0 * 1.0025_f64.powi(k)).sum();
+        assert!(
+            (net_worth(&depositing(&second))
+                - net_worth(&second)
+                - yearly_deposits * 1.0025_f64.powi(12))
+            .abs()
+                < 1e-6
+        );
+
+        let separate = simulate(AccountMode::Separate);
+        assert_eq!(names(&separate), ["Carro (Ana)", "Curso (Bia)"]);
+        assert_eq!(separate.cash_flow_breakdown[1].1[0], -300.0);
+        let retired: Vec<&str> = separate
+            .retirements
+            .iter()
+            .map(|retirement| retirement.name.as_str())
+            .collect();
+        assert_eq!(retired, ["Ana", "Bia"]);
+    }
+}
@@ -1,16 +1,23 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 mod calculation;
+mod household;
+mod mcmv;
 mod model;
 mod plotting;
+mod reconciliation;
 mod ui_components;
 
-use calculation::{
-    calculate_money_timeseries_price, calculate_money_timeseries_sac, AmortizationStrategyType,
-};
+use calculation::{calculate_reserve_report, AmortizationStrategyType};
 use eframe::egui;
 use plotting::format_with_thousands_separator;
 use ui_components::{
-    render_buyer_params, render_house_params, render_kpis, render_plot, render_simulation_params,
+    render_additional_loans, render_adjustable_rate_params, render_bank_schedule_reconciliation,
+    render_buyer_params, render_cash_flow_items, render_co_buyers, render_construction_params,
+    render_escrow_params, render_expense_categories, render_grace_period_params,
+    render_home_equity_params, render_house_params, render_intermediate_payments_params,
+    render_kpis, render_mcmv_params, render_mcmv_warnings, render_offers, render_offers_params,
+    render_plot, render_refinancing_params, render_rental_params, render_reverse_calculator,
+    render_sale_params, render_simulation_params, render_trade_up_params, ResultView,
 };
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -83,64 +90,210 @@ enum PlotSelection {
     #[default]
     MoneyInAccount,
     Payments,
+    StackedPayments,
+    NetWorth,
+    Runway,
+    CashFlows,
+    Expenses,
+    Offers,
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+enum OfferRanking {
+    #[default]
+    TotalCost,
+    EffectiveCost,
+    FirstInstallment,
+    FinalNetWorth,
+    MinimumMoney,
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+enum ValueView {
+    #[default]
+    Nominal,
+    Real,
+    Both,
 }
 
 #[derive(Default)]
 struct MyApp {
     buyer: model::Buyer,
+    /// Buyers who combine their incomes with `buyer` (`composição de renda`).
+    co_buyers: Vec<model::Buyer>,
+    account_mode: model::AccountMode,
     house: model::House,
     simulation: model::Simulation,
+    mcmv: model::Mcmv,
+    trade_up: model::TradeUp,
+    additional_loans: Vec<model::AdditionalLoan>,
+    reverse_calculator: model::ReverseCalculator,
+    offers: Vec<model::Offer>,
+    offer_ranking: OfferRanking,
+    /// Schedule pasted from the bank, as CSV.
+    bank_schedule: String,
 
     strategy: calculation::AmortizationStrategyType,
     plot_selection: PlotSelection,
+    value_view: ValueView,
+}
+
+impl MyApp {
+    /// Gets the house as simulated, with the `MCMV` terms and the buyers'
+    /// insurance applied.
+    fn simulated_house(&self) -> model::House {
+        let buyers: Vec<&model::Buyer> = std::iter::once(&self.buyer)
+            .chain(&self.co_buyers)
+            .collect();
+        let house = mcmv::apply(
+            &self.mcmv,
+            &self.house,
+            self.strategy,
+            household::gross_income(&buyers),
+        );
+        household::apply_insurance(&buyers, &house)
+    }
 }
 
 impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
+        let buyers: Vec<&model::Buyer> = std::iter::once(&self.buyer)
+            .chain(&self.co_buyers)
+            .collect();
+        let mcmv_assessment = mcmv::assess(
+            &self.mcmv,
+            &self.house,
+            self.strategy,
+            household::gross_income(&buyers),
+        );
+
+        egui::SidePanel::left("params").show(ctx, |ui| {
             ui.style_mut().spacing.slider_width = 120.0;
 
-            render_buyer_params(ui, &mut self.buyer);
-            render_house_params(ui, &mut self.house, self.strategy);
-            render_simulation_params(
-                ui,
-                &mut self.simulation,
-                &mut self.strategy,
-                &mut self.plot_selection,
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                render_buyer_params(ui, &mut self.buyer);
+                render_co_buyers(
+                    ui,
+                    &mut self.co_buyers,
+                    &mut self.buyer.installment_share,
+                    &mut self.account_mode,
+                );
+                render_expense_categories(ui, &mut self.buyer.expense_categories);
+                render_cash_flow_items(ui, &mut self.buyer.cash_flows);
+                render_house_params(ui, &mut self.house, self.strategy);
+                render_mcmv_params(ui, &mut self.mcmv, &mcmv_assessment);
+                render_escrow_params(ui, &mut self.house.fees);
+                render_grace_period_params(ui, &mut self.house.grace_period);
+                render_intermediate_payments_params(ui, &mut self.house.intermediate_payments);
+                render_construction_params(ui, &mut self.house.construction);
+                render_refinancing_params(ui, &mut self.house.refinancing);
+                render_adjustable_rate_params(ui, &mut self.house.adjustable_rate);
+                render_home_equity_params(ui, &mut self.house.home_equity);
+                render_rental_params(ui, &mut self.house.rental);
+                render_sale_params(ui, &mut self.house.sale);
+                render_trade_up_params(ui, &mut self.trade_up, &self.house.sale);
+                render_additional_loans(ui, &mut self.additional_loans);
+                render_offers_params(ui, &mut self.offers);
+                render_reverse_calculator(ui, &mut self.reverse_calculator);
+                let house = self.simulated_house();
+                render_bank_schedule_reconciliation(
+                    ui,
+                    &mut self.bank_schedule,
+                    &house,
+                    self.strategy,
+                );
+                render_simulation_params(
+                    ui,
+                    &mut self.simulation,
+                    &mut self.strategy,
+                    &mut self.plot_selection,
+                    &mut self.value_view,
+                );
+            });
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let buyers: Vec<&model::Buyer> = std::iter::once(&self.buyer)
+                .chain(&self.co_buyers)
+                .collect();
+            let household_buyer = household::combine(&buyers, &self.simulation);
+            let house = self.simulated_house();
+
+            let sim_output = household::calculate_household_timeseries(
+                &buyers,
+                self.account_mode,
+                &house,
+                &self.simulation,
+                self.strategy,
+                &self.trade_up,
+                &self.additional_loans,
             );
 
-            let sim_output = match self.strategy {
-                AmortizationStrategyType::Price => calculate_money_timeseries_price(
-                    self.simulation.months_to_forecast,
-                    self.buyer.starting_money,
-                    self.house.down_payment,
-                    self.house.house_price,
-                    self.house.house_monthly_interest,
-                    self.house.months_to_pay,
-                    self.buyer.liquid_salary,
-                    self.buyer.fixed_monthly_expenses,
-                    self.buyer.investment_monthly_interest,
-                    self.buyer.yearly_bonus,
-                    self.simulation.inflation,
-                ),
-                AmortizationStrategyType::Sac => calculate_money_timeseries_sac(
-                    self.simulation.months_to_forecast,
-                    self.buyer.starting_money,
-                    self.house.down_payment,
-                    self.house.house_price,
-                    self.house.house_monthly_interest,
-                    self.house.months_to_pay,
-                    self.buyer.liquid_salary,
-                    self.buyer.fixed_monthly_expenses,
-                    self.buyer.investment_monthly_interest,
-                    self.buyer.yearly_bonus,
-                    self.house.yearly_extra_amortization,
-                    self.simulation.inflation,
-                ),
+            let real_output = sim_output.deflated(self.simulation.inflation);
+
+            let reserve_report =
+                calculate_reserve_report(&sim_output, household_buyer.minimum_reserve_months);
+            let real_reserve_report =
+                calculate_reserve_report(&real_output, household_buyer.minimum_reserve_months);
+
+            let nominal_view = ResultView {
+                label: "nominal",
+                sim_output: &sim_output,
+                reserve_report: &reserve_report,
+            };
+            let real_view = ResultView {
+                label: "real",
+                sim_output: &real_output,
+                reserve_report: &real_reserve_report,
             };
 
-            render_kpis(ui, &sim_output, &self.simulation);
-            render_plot(ui, &sim_output, self.plot_selection);
+            let views = match self.value_view {
+                ValueView::Nominal => vec![nominal_view],
+                ValueView::Real => vec![real_view],
+                ValueView::Both => vec![nominal_view, real_view],
+            };
+
+            if self.mcmv.enabled {
+                render_mcmv_warnings(ui, &mcmv_assessment);
+            }
+            render_kpis(ui, &views, &self.simulation);
+            if self.plot_selection == PlotSelection::Offers {
+                let nominal_outputs: Vec<_> = self
+                    .offers
+                    .iter()
+                    .map(|offer| {
+                        household::calculate_household_timeseries(
+                            &buyers,
+                            self.account_mode,
+                            &household::apply_insurance(&buyers, &offer.apply_to(&house)),
+                            &self.simulation,
+                            offer.strategy,
+                            &self.trade_up,
+                            &self.additional_loans,
+                        )
+                    })
+                    .collect();
+                let real_outputs: Vec<_> = nominal_outputs
+                    .iter()
+                    .map(|output| output.deflated(self.simulation.inflation))
+                    .collect();
+
+                let offer_views = match self.value_view {
+                    ValueView::Nominal => vec![("nominal", nominal_outputs)],
+                    ValueView::Real => vec![("real", real_outputs)],
+                    ValueView::Both => vec![("nominal", nominal_outputs), ("real", real_outputs)],
+                };
+
+                render_offers(
+                    ui,
+                    &self.offers,
+                    &offer_views,
+                    &self.simulation,
+                    &mut self.offer_ranking,
+                );
+            } else {
+                render_plot(ui, &views, &self.simulation, self.plot_selection);
+            }
         });
     }
 }
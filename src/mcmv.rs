@@ -0,0 +1,258 @@
+use crate::{
+    calculation::{calculate_loan_schedule, AmortizationStrategyType},
+    model::{House, Mcmv, Region},
+};
+
+/// Longest term, in months, financed by the program.
+const MAX_MONTHS_TO_PAY: i32 = 420;
+/// Share of the gross income the first installment may take.
+const MAX_INCOME_COMMITMENT: f64 = 0.3;
+
+/// Income band (`faixa`) of Minha Casa Minha Vida in urban areas, as of 2025.
+pub(crate) struct Band {
+    pub(crate) name: &'static str,
+    /// Greatest gross monthly income of the household in the band.
+    pub(crate) max_income: f64,
+    /// Yearly rate in the North and Northeast, and in the other regions.
+    yearly_interest: [f64; 2],
+    pub(crate) max_property_value: f64,
+    /// Greatest direct subsidy, as the actual one depends on the city and the
+    /// family.
+    pub(crate) max_subsidy: f64,
+}
+
+const BANDS: [Band; 4] = [
+    Band {
+        name: "Faixa 1",
+        max_income: 2_850.0,
+        yearly_interest: [0.04, 0.0425],
+        max_property_value: 264_000.0,
+        max_subsidy: 55_000.0,
+    },
+    Band {
+        name: "Faixa 2",
+        max_income: 4_700.0,
+        yearly_interest: [0.0475, 0.05],
+        max_property_value: 264_000.0,
+        max_subsidy: 55_000.0,
+    },
+    Band {
+        name: "Faixa 3",
+        max_income: 8_600.0,
+        yearly_interest: [0.0766, 0.0816],
+        max_property_value: 350_000.0,
+        max_subsidy: 0.0,
+    },
+    Band {
+        name: "Faixa 4",
+        max_income: 12_000.0,
+        yearly_interest: [0.1, 0.1],
+        max_property_value: 500_000.0,
+        max_subsidy: 0.0,
+    },
+];
+
+impl Band {
+    pub(crate) fn monthly_interest(&self, region: Region) -> f64 {
+        let yearly_interest = match region {
+            Region::NorthNortheast => self.yearly_interest[0],
+            Region::Other => self.yearly_interest[1],
+        };
+
+        (1.0 + yearly_interest).powf(1.0 / 12.0) - 1.0
+    }
+}
+
+/// Gets the band of the household's gross monthly income, if it is in the
+/// program.
+pub(crate) fn find_band(gross_monthly_income: f64) -> Option<&'static Band> {
+    BANDS
+        .iter()
+        .find(|band| gross_monthly_income <= band.max_income)
+}
+
+/// How the house fits the program.
+pub(crate) struct Assessment {
+    pub(crate) band: Option<&'static Band>,
+    /// Whether the house can be financed by the program at all.
+    pub(crate) eligible: bool,
+    pub(crate) monthly_interest: f64,
+    pub(crate) subsidy: f64,
+    pub(crate) warnings: Vec<String>,
+}
+
+/// Assesses the house against the band of the household's combined gross
+/// income.
+pub(crate) fn assess(
+    program: &Mcmv,
+    house: &House,
+    strategy: AmortizationStrategyType,
+    gross_monthly_income: f64,
+) -> Assessment {
+    let Some(band) = find_band(gross_monthly_income) else {
+        return Assessment {
+            band: None,
+            eligible: false,
+            monthly_interest: house.house_monthly_interest,
+            subsidy: 0.0,
+            warnings: vec![format!(
+                "Renda bruta acima do limite do programa ({})",
+                BANDS[BANDS.len() - 1].max_income
+            )],
+        };
+    };
+
+    let mut warnings = Vec::new();
+    let eligible = house.house_price <= band.max_property_value;
+
+    if !eligible {
+        warnings.push(format!(
+            "Imóvel acima do valor máximo da {} ({})",
+            band.name, band.max_property_value
+        ));
+    }
+
+    if house.months_to_pay > MAX_MONTHS_TO_PAY {
+        warnings.push(format!(
+            "Prazo acima do máximo do programa ({} meses)",
+            MAX_MONTHS_TO_PAY
+        ));
+    }
+
+    let monthly_interest = band.monthly_interest(program.region);
+    let subsidy = program
+        .subsidy
+        .unwrap_or(band.max_subsidy)
+        .min(band.max_subsidy)
+        .min(house.house_price - house.down_payment)
+        .max(0.0);
+
+    let first_payment =
+        calculate_loan_schedule(&subsidized(house, monthly_interest, subsidy), strategy, 0)
+            .monthly_payments
+            .first()
+            .copied()
+            .unwrap_or(0.0);
+
+    if eligible && first_payment > gross_monthly_income * MAX_INCOME_COMMITMENT {
+        warnings.push(format!(
+            "Primeira parcela acima de {:.0}% da renda bruta",
+            MAX_INCOME_COMMITMENT * 100.0
+        ));
+    }
+
+    Assessment {
+        band: Some(band),
+        eligible,
+        monthly_interest,
+        subsidy,
+        warnings,
+    }
+}
+
+/// Finances the house through the program, with the band's rate and the
+/// subsidy deducted from the loan. Houses outside the program are kept as
+/// they are.
+pub(crate) fn apply(
+    program: &Mcmv,
+    house: &House,
+    strategy: AmortizationStrategyType,
+    gross_monthly_income: f64,
+) -> House {
+    if !program.enabled {
+        return house.clone();
+    }
+
+    let assessment = assess(program, house, strategy, gross_monthly_income);
+
+    if assessment.eligible {
+        subsidized(house, assessment.monthly_interest, assessment.subsidy)
+    } else {
+        house.clone()
+    }
+}
+
+fn subsidized(house: &House, monthly_interest: f64, subsidy: f64) -> House {
+    House {
+        house_monthly_interest: monthly_interest,
+        subsidy,
+        ..house.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_band() {
+        assert_eq!(find_band(2_000.0).unwrap().name, "Faixa 1");
+        assert_eq!(find_band(4_700.0).unwrap().name, "Faixa 2");
+        assert_eq!(find_band(8_000.0).unwrap().name, "Faixa 3");
+        assert_eq!(find_band(12_000.0).unwrap().name, "Faixa 4");
+        assert!(find_band(12_000.01).is_none());
+
+        let monthly_interest = BANDS[0].monthly_interest(Region::NorthNortheast);
+        assert!(((1.0 + monthly_interest).powi(12) - 1.04).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_apply() {
+        let program = Mcmv {
+            enabled: true,
+            region: Region::Other,
+            subsidy: None,
+        };
+        let house = House {
+            house_price: 250_000.0,
+            down_payment: 25_000.0,
+            months_to_pay: 360,
+            ..Default::default()
+        };
+
+        let financed = apply(&program, &house, AmortizationStrategyType::Price, 4_000.0);
+        assert_eq!(financed.subsidy, 55_000.0);
+        assert!(
+            (financed.house_monthly_interest - BANDS[1].monthly_interest(Region::Other)).abs()
+                < 1e-15
+        );
+
+        let loan = calculate_loan_schedule(&financed, AmortizationStrategyType::Price, 0);
+        assert!((loan.balances[0] - 170_000.0).abs() < 1e-9);
+
+        // A subsidy granted below the maximum, and one above it.
+        let granted = |subsidy| Mcmv {
+            subsidy: Some(subsidy),
+            ..program.clone()
+        };
+        let strategy = AmortizationStrategyType::Price;
+        assert_eq!(
+            apply(&granted(20_000.0), &house, strategy, 4_000.0).subsidy,
+            20_000.0
+        );
+        assert_eq!(
+            apply(&granted(80_000.0), &house, strategy, 4_000.0).subsidy,
+            55_000.0
+        );
+
+        // Too expensive for the band, and too long for the program.
+        let house = House {
+            house_price: 300_000.0,
+            months_to_pay: 480,
+            ..house
+        };
+        let assessment = assess(&program, &house, AmortizationStrategyType::Price, 4_000.0);
+        assert!(!assessment.eligible);
+        assert_eq!(assessment.warnings.len(), 2);
+        assert_eq!(
+            apply(&program, &house, AmortizationStrategyType::Price, 4_000.0)
+                .house_monthly_interest,
+            house.house_monthly_interest
+        );
+
+        // A higher income pays more, in a band with no subsidy.
+        let assessment = assess(&program, &house, AmortizationStrategyType::Price, 8_000.0);
+        assert!(assessment.eligible);
+        assert_eq!(assessment.subsidy, 0.0);
+    }
+}
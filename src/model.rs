@@ -1,39 +1,761 @@
+use crate::calculation::AmortizationStrategyType;
+
+#[derive(Clone)]
 pub(crate) struct Buyer {
+    pub(crate) name: String,
+    pub(crate) age: i32,
     pub(crate) starting_money: f64,
     pub(crate) liquid_salary: f64,
+    /// Salary before taxes, which counts for the income requirements of the
+    /// financing.
+    pub(crate) gross_salary: f64,
     pub(crate) fixed_monthly_expenses: f64,
     pub(crate) investment_monthly_interest: f64,
+    pub(crate) overdraft_monthly_interest: f64,
     pub(crate) yearly_bonus: f64,
+    /// Calendar month, from 1 to 12, in which the bonus is paid.
+    pub(crate) bonus_month: i32,
+    /// Whether the 13th salary is paid, half in November and half in December.
+    pub(crate) thirteenth_salary: bool,
+    /// Balance of the `FGTS`, which pays for the down payment of homes.
+    pub(crate) fgts_balance: f64,
+    /// Deposited by the employer each month, 8% of the gross salary for `CLT`
+    /// employees.
+    pub(crate) fgts_monthly_deposit: f64,
+    /// Deposits into the `FGTS` besides `fgts_monthly_deposit`, such as those
+    /// of co-buyers, which stop once each of them retires.
+    pub(crate) fgts_deposits: Vec<CashFlowItem>,
+    /// Share of the installments, down payments and rent of the houses paid
+    /// by this buyer, when the buyers keep separate accounts.
+    pub(crate) installment_share: f64,
+    pub(crate) minimum_reserve_months: f64,
+    pub(crate) retirement: Retirement,
+    pub(crate) cash_flows: Vec<CashFlowItem>,
+    pub(crate) expense_categories: Vec<ExpenseCategory>,
 }
 
 impl Default for Buyer {
     fn default() -> Self {
         Buyer {
+            name: "Comprador".to_string(),
+            age: 35,
             starting_money: 600_000.0,
             liquid_salary: 20_000.0,
+            gross_salary: 27_000.0,
             fixed_monthly_expenses: 7_000.0,
             investment_monthly_interest: 0.01,
+            overdraft_monthly_interest: 0.08,
             yearly_bonus: 0.0,
+            bonus_month: 12,
+            thirteenth_salary: false,
+            fgts_balance: 0.0,
+            fgts_monthly_deposit: 0.0,
+            fgts_deposits: Vec::new(),
+            installment_share: 1.0,
+            minimum_reserve_months: 6.0,
+            retirement: Retirement::default(),
+            cash_flows: Vec::new(),
+            expense_categories: Vec::new(),
+        }
+    }
+}
+
+impl Buyer {
+    /// Someone who combines their income with the buyer's.
+    pub(crate) fn co_buyer() -> Self {
+        Buyer {
+            name: "Co-comprador".to_string(),
+            starting_money: 50_000.0,
+            liquid_salary: 8_000.0,
+            gross_salary: 10_500.0,
+            fixed_monthly_expenses: 0.0,
+            installment_share: 0.5,
+            ..Buyer::default()
+        }
+    }
+}
+
+/// How much may be withdrawn from the savings each month after retiring.
+///
+/// The general expenses are cut for the withdrawals to stay within the rule,
+/// while the installments and the other expenses are always paid.
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum WithdrawalRule {
+    /// Whatever the expenses need.
+    #[default]
+    AsNeeded,
+    /// A yearly rate over the savings on retirement, corrected by inflation
+    /// (the "4% rule").
+    FixedRate,
+    /// A yearly rate over the current savings.
+    PercentOfBalance,
+}
+
+/// The buyer stops receiving the salary, bonus and `FGTS` deposits on
+/// reaching `age`, and starts receiving the pension (`INSS`).
+#[derive(Clone)]
+pub(crate) struct Retirement {
+    pub(crate) enabled: bool,
+    pub(crate) age: i32,
+    /// Monthly pension in today's money, corrected by inflation.
+    pub(crate) monthly_pension: f64,
+    pub(crate) withdrawal_rule: WithdrawalRule,
+    pub(crate) yearly_withdrawal_rate: f64,
+    /// Age until which the savings should last.
+    pub(crate) target_age: i32,
+}
+
+impl Default for Retirement {
+    fn default() -> Self {
+        Retirement {
+            enabled: false,
+            age: 65,
+            monthly_pension: 5_000.0,
+            withdrawal_rule: WithdrawalRule::default(),
+            yearly_withdrawal_rate: 0.04,
+            target_age: 95,
         }
     }
 }
 
+impl Retirement {
+    /// Gets the months of the simulation before the buyer of the given age
+    /// retires.
+    pub(crate) fn months_until(&self, age: i32) -> i32 {
+        ((self.age - age) * 12).max(0)
+    }
+
+    /// Gets the months of the simulation before the buyer of the given age
+    /// reaches `target_age`.
+    pub(crate) fn months_until_target(&self, age: i32) -> i32 {
+        ((self.target_age - age) * 12).max(0)
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum CashFlowFrequency {
+    #[default]
+    Monthly,
+    Yearly,
+    OneOff,
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Indexation {
+    #[default]
+    None,
+    Inflation,
+    Custom,
+}
+
+/// A user-defined income or expense, such as a car payment or a condo fee.
+///
+/// Months are counted from 1, and `end_month` is inclusive.
+#[derive(Clone)]
+pub(crate) struct CashFlowItem {
+    pub(crate) name: String,
+    pub(crate) amount: f64,
+    pub(crate) is_income: bool,
+    pub(crate) start_month: i32,
+    pub(crate) end_month: Option<i32>,
+    pub(crate) frequency: CashFlowFrequency,
+    pub(crate) indexation: Indexation,
+    pub(crate) custom_monthly_rate: f64,
+}
+
+impl Default for CashFlowItem {
+    fn default() -> Self {
+        CashFlowItem {
+            name: "Novo item".to_string(),
+            amount: 1_000.0,
+            is_income: false,
+            start_month: 1,
+            end_month: None,
+            frequency: CashFlowFrequency::Monthly,
+            indexation: Indexation::Inflation,
+            custom_monthly_rate: 0.0,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum ExpenseGrowth {
+    /// Grows with the simulation's inflation plus `monthly_rate`.
+    #[default]
+    InflationPlusSpread,
+    /// Grows with `monthly_rate` alone.
+    Custom,
+}
+
+/// Monthly expenses, such as healthcare or education, that grow at their own
+/// pace instead of following general inflation as `fixed_monthly_expenses` do.
+#[derive(Clone)]
+pub(crate) struct ExpenseCategory {
+    pub(crate) name: String,
+    pub(crate) monthly_amount: f64,
+    pub(crate) growth: ExpenseGrowth,
+    pub(crate) monthly_rate: f64,
+}
+
+impl Default for ExpenseCategory {
+    fn default() -> Self {
+        ExpenseCategory {
+            name: "Nova categoria".to_string(),
+            monthly_amount: 1_000.0,
+            growth: ExpenseGrowth::InflationPlusSpread,
+            monthly_rate: 0.002,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub(crate) struct House {
+    pub(crate) name: String,
     pub(crate) house_price: f64,
     pub(crate) down_payment: f64,
+    /// Direct subsidy of a housing program, which lowers the amount financed
+    /// without coming from the buyer.
+    pub(crate) subsidy: f64,
     pub(crate) house_monthly_interest: f64,
     pub(crate) months_to_pay: i32,
     pub(crate) yearly_extra_amortization: f64,
+    pub(crate) monthly_appreciation: f64,
+    /// How the schedule is rounded to centavos, as in the bank's statement.
+    pub(crate) rounding: RoundingMode,
+    pub(crate) payment_frequency: PaymentFrequency,
+    /// How the days between installments are counted to accrue the interest.
+    pub(crate) day_count: DayCount,
+    pub(crate) fees: LoanFees,
+    pub(crate) grace_period: GracePeriod,
+    pub(crate) intermediate_payments: IntermediatePayments,
+    pub(crate) construction: ConstructionPhase,
+    pub(crate) refinancing: Refinancing,
+    pub(crate) adjustable_rate: AdjustableRate,
+    pub(crate) home_equity: HomeEquity,
+    pub(crate) rental: Rental,
+    pub(crate) sale: Sale,
 }
 
 impl Default for House {
     fn default() -> Self {
         House {
+            name: "Imóvel".to_string(),
             house_price: 600_000.0,
             down_payment: 150_000.0,
+            subsidy: 0.0,
             house_monthly_interest: 0.01,
             months_to_pay: 120,
             yearly_extra_amortization: 0.0,
+            monthly_appreciation: 0.004,
+            rounding: RoundingMode::default(),
+            payment_frequency: PaymentFrequency::default(),
+            day_count: DayCount::default(),
+            fees: LoanFees::default(),
+            grace_period: GracePeriod::default(),
+            intermediate_payments: IntermediatePayments::default(),
+            construction: ConstructionPhase::default(),
+            refinancing: Refinancing::default(),
+            adjustable_rate: AdjustableRate::default(),
+            home_equity: HomeEquity::default(),
+            rental: Rental::default(),
+            sale: Sale::default(),
+        }
+    }
+}
+
+/// A property or loan running alongside the main house, e.g. a rental
+/// apartment or a car loan, with its own financing.
+pub(crate) struct AdditionalLoan {
+    /// What is financed; only its price, down payment and financing are used
+    /// when it is not a property.
+    pub(crate) house: House,
+    pub(crate) strategy: AmortizationStrategyType,
+    /// Months before it is taken, with its down payment paid on the last of
+    /// them.
+    pub(crate) purchased_after: i32,
+    /// Whether it finances a property, which appreciates, can be rented and
+    /// counts in the net worth.
+    pub(crate) is_property: bool,
+}
+
+impl Default for AdditionalLoan {
+    fn default() -> Self {
+        AdditionalLoan {
+            house: House {
+                name: "Carro".to_string(),
+                house_price: 100_000.0,
+                down_payment: 20_000.0,
+                house_monthly_interest: 0.015,
+                months_to_pay: 48,
+                monthly_appreciation: 0.0,
+                ..House::default()
+            },
+            strategy: AmortizationStrategyType::Price,
+            purchased_after: 0,
+            is_property: false,
+        }
+    }
+}
+
+/// Buys `house` with the proceeds of the sale of the first house, in the
+/// month it is sold.
+pub(crate) struct TradeUp {
+    pub(crate) enabled: bool,
+    /// The next home, whose `down_payment` is the cash added on top of the
+    /// proceeds.
+    pub(crate) house: House,
+    pub(crate) strategy: AmortizationStrategyType,
+}
+
+impl Default for TradeUp {
+    fn default() -> Self {
+        TradeUp {
+            enabled: false,
+            house: House {
+                name: "Segundo Imóvel".to_string(),
+                house_price: 1_200_000.0,
+                down_payment: 0.0,
+                months_to_pay: 360,
+                ..House::default()
+            },
+            strategy: AmortizationStrategyType::Sac,
+        }
+    }
+}
+
+/// Sells the house on `month`, counted from the start of the simulation,
+/// paying off the financing with the proceeds.
+#[derive(Clone)]
+pub(crate) struct Sale {
+    pub(crate) enabled: bool,
+    pub(crate) month: i32,
+    /// Fraction of the sale price paid to the broker.
+    pub(crate) broker_commission: f64,
+    /// Fraction of the proceeds used to buy another home within 180 days,
+    /// which exempts the same fraction of the capital gain.
+    pub(crate) reinvested_fraction: f64,
+}
+
+impl Default for Sale {
+    fn default() -> Self {
+        Sale {
+            enabled: false,
+            month: 84,
+            broker_commission: 0.06,
+            reinvested_fraction: 0.0,
+        }
+    }
+}
+
+/// Rents the house out from `start_month` on, counted from the start of the
+/// simulation.
+#[derive(Clone)]
+pub(crate) struct Rental {
+    pub(crate) enabled: bool,
+    pub(crate) start_month: i32,
+    /// Rent charged on `start_month`.
+    pub(crate) monthly_rent: f64,
+    /// Adjustment of the rent on each anniversary of the contract, e.g. IGP-M.
+    pub(crate) yearly_adjustment: f64,
+    /// Fraction of the time the house stays empty.
+    pub(crate) vacancy_rate: f64,
+    /// Fraction of the rent charged by the real estate agency.
+    pub(crate) agency_fee: f64,
+}
+
+impl Default for Rental {
+    fn default() -> Self {
+        Rental {
+            enabled: false,
+            start_month: 1,
+            monthly_rent: 3_000.0,
+            yearly_adjustment: 0.05,
+            vacancy_rate: 0.08,
+            agency_fee: 0.1,
+        }
+    }
+}
+
+/// Moves the outstanding balance of the financing into a new loan
+/// (`portabilidade` or refinancing) at `month`, counted from the start of the
+/// simulation.
+#[derive(Clone)]
+pub(crate) struct Refinancing {
+    pub(crate) enabled: bool,
+    pub(crate) month: i32,
+    pub(crate) monthly_interest: f64,
+    pub(crate) strategy: AmortizationStrategyType,
+    pub(crate) months_to_pay: i32,
+    /// Fees, taxes and appraisal paid when switching.
+    pub(crate) switching_costs: f64,
+}
+
+impl Default for Refinancing {
+    fn default() -> Self {
+        Refinancing {
+            enabled: false,
+            month: 60,
+            monthly_interest: 0.008,
+            strategy: AmortizationStrategyType::Sac,
+            months_to_pay: 300,
+            switching_costs: 5_000.0,
+        }
+    }
+}
+
+/// Makes the rate of the financing follow an index plus a margin, such as
+/// Euribor plus a spread or a 5/1 ARM, after a fixed initial period.
+///
+/// The rates are monthly, and the caps are how much it may rise or fall on
+/// each reset (`periodic_cap`) and rise above the initial rate over the life
+/// of the loan (`lifetime_cap`).
+#[derive(Clone)]
+pub(crate) struct AdjustableRate {
+    pub(crate) enabled: bool,
+    /// Months paid at the initial rate before the first reset.
+    pub(crate) fixed_months: i32,
+    pub(crate) reset_months: i32,
+    /// Index on each reset, the last one holding for the rest of the loan.
+    pub(crate) index_path: Vec<f64>,
+    pub(crate) margin: f64,
+    pub(crate) periodic_cap: f64,
+    pub(crate) lifetime_cap: f64,
+    pub(crate) floor: f64,
+}
+
+impl Default for AdjustableRate {
+    fn default() -> Self {
+        AdjustableRate {
+            enabled: false,
+            fixed_months: 60,
+            reset_months: 12,
+            index_path: vec![0.003, 0.0035, 0.004],
+            margin: 0.002,
+            periodic_cap: 0.0017,
+            lifetime_cap: 0.0042,
+            floor: 0.0,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Region {
+    /// North and Northeast, with lower rates.
+    NorthNortheast,
+    #[default]
+    Other,
+}
+
+/// Finances the house through Minha Casa Minha Vida, whose band follows the
+/// household's gross income.
+#[derive(Default, Clone)]
+pub(crate) struct Mcmv {
+    pub(crate) enabled: bool,
+    pub(crate) region: Region,
+    /// Direct subsidy granted, which depends on the city and the family.
+    /// `None` assumes the band's maximum.
+    pub(crate) subsidy: Option<f64>,
+}
+
+/// How the money of the buyers who combine their incomes is kept.
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum AccountMode {
+    /// A single account, receiving all incomes and paying all expenses.
+    #[default]
+    Combined,
+    /// An account for each buyer, who pays their `installment_share` of the
+    /// houses.
+    Separate,
+}
+
+/// Charges of the bank on top of the interest.
+#[derive(Clone)]
+pub(crate) struct LoanFees {
+    /// Paid when the contract is signed, e.g. appraisal and registry fees.
+    pub(crate) upfront: f64,
+    /// Administration fee charged with each installment.
+    pub(crate) monthly_fee: f64,
+    /// Insurance (`MIP`) over the outstanding balance, charged with each
+    /// installment.
+    pub(crate) monthly_insurance_rate: f64,
+    /// Whether the `MIP` rate follows the ages of the buyers instead.
+    pub(crate) insurance_by_age: bool,
+    /// Private mortgage insurance (`PMI`) over the amount financed, charged
+    /// until the balance falls to `pmi_max_loan_to_value` of the home's value.
+    pub(crate) pmi_monthly_rate: f64,
+    pub(crate) pmi_max_loan_to_value: f64,
+    /// Property tax over the home's value, escrowed with each installment.
+    pub(crate) monthly_property_tax_rate: f64,
+    /// Homeowner's insurance escrowed with each installment.
+    pub(crate) monthly_homeowners_insurance: f64,
+}
+
+impl Default for LoanFees {
+    fn default() -> Self {
+        LoanFees {
+            upfront: 0.0,
+            monthly_fee: 0.0,
+            monthly_insurance_rate: 0.0,
+            insurance_by_age: false,
+            pmi_monthly_rate: 0.0,
+            pmi_max_loan_to_value: 0.8,
+            monthly_property_tax_rate: 0.0,
+            monthly_homeowners_insurance: 0.0,
+        }
+    }
+}
+
+/// A bank's offer to finance the house, compared against the others.
+pub(crate) struct Offer {
+    pub(crate) name: String,
+    pub(crate) monthly_interest: f64,
+    pub(crate) months_to_pay: i32,
+    pub(crate) strategy: AmortizationStrategyType,
+    pub(crate) fees: LoanFees,
+}
+
+impl Default for Offer {
+    fn default() -> Self {
+        Offer {
+            name: "Banco".to_string(),
+            monthly_interest: 0.0095,
+            months_to_pay: 360,
+            strategy: AmortizationStrategyType::Sac,
+            fees: LoanFees {
+                upfront: 3_500.0,
+                monthly_fee: 25.0,
+                monthly_insurance_rate: 0.0003,
+                ..LoanFees::default()
+            },
+        }
+    }
+}
+
+impl Offer {
+    /// Gets the house financed under this offer.
+    ///
+    /// Only the bank's fees and insurance come from the offer, while `PMI`,
+    /// escrow and whether the insurance follows the buyers' ages are kept.
+    pub(crate) fn apply_to(&self, house: &House) -> House {
+        House {
+            name: self.name.clone(),
+            house_monthly_interest: self.monthly_interest,
+            months_to_pay: self.months_to_pay,
+            fees: LoanFees {
+                upfront: self.fees.upfront,
+                monthly_fee: self.fees.monthly_fee,
+                monthly_insurance_rate: self.fees.monthly_insurance_rate,
+                ..house.fees.clone()
+            },
+            ..house.clone()
+        }
+    }
+}
+
+/// How often the installments of the loan are paid.
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum PaymentFrequency {
+    #[default]
+    Monthly,
+    /// Every two weeks, 26 installments a year.
+    Biweekly,
+    /// Every week, 52 installments a year.
+    Weekly,
+}
+
+impl PaymentFrequency {
+    pub(crate) fn periods_per_year(self) -> i32 {
+        match self {
+            PaymentFrequency::Monthly => 12,
+            PaymentFrequency::Biweekly => 26,
+            PaymentFrequency::Weekly => 52,
+        }
+    }
+}
+
+/// Day-count convention, telling how many days a year and each period have
+/// when the interest is accrued.
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum DayCount {
+    /// Months of 30 days in a year of 360.
+    #[default]
+    Thirty360,
+    /// Calendar days in a year of 365.
+    Actual365,
+    /// Business days in a year of 252, as the `DI` rate.
+    Business252,
+}
+
+impl DayCount {
+    pub(crate) fn days_in_year(self) -> f64 {
+        match self {
+            DayCount::Thirty360 => 360.0,
+            DayCount::Actual365 => 365.0,
+            DayCount::Business252 => 252.0,
+        }
+    }
+
+    /// Gets the days counted between two installments.
+    pub(crate) fn days_in_period(self, frequency: PaymentFrequency) -> f64 {
+        let days_in_week = match self {
+            DayCount::Thirty360 | DayCount::Actual365 => 7.0,
+            DayCount::Business252 => 5.0,
+        };
+
+        match frequency {
+            PaymentFrequency::Monthly => self.days_in_year() / 12.0,
+            PaymentFrequency::Biweekly => 2.0 * days_in_week,
+            PaymentFrequency::Weekly => days_in_week,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum RoundingMode {
+    /// Values are kept unrounded.
+    #[default]
+    None,
+    /// Halves are rounded away from zero.
+    HalfUp,
+    /// Halves are rounded to the even centavo (banker's rounding).
+    HalfEven,
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum GracePeriodMode {
+    /// Only the interest is paid, keeping the balance.
+    #[default]
+    InterestOnly,
+    /// Nothing is paid, and the interest is added to the balance.
+    Capitalized,
+}
+
+/// Months at the start of the financing (`carência`) without amortization,
+/// after which the amortization system runs over the rest of the term.
+#[derive(Clone)]
+pub(crate) struct GracePeriod {
+    pub(crate) enabled: bool,
+    pub(crate) months: i32,
+    pub(crate) mode: GracePeriodMode,
+}
+
+impl Default for GracePeriod {
+    fn default() -> Self {
+        GracePeriod {
+            enabled: false,
+            months: 6,
+            mode: GracePeriodMode::InterestOnly,
+        }
+    }
+}
+
+/// Contractual payments (`parcelas intermediárias`) due on top of the
+/// installments, common when the builder or the seller finances the house.
+///
+/// Unlike extra amortizations, they are mandatory and lower the installments.
+#[derive(Clone)]
+pub(crate) struct IntermediatePayments {
+    pub(crate) enabled: bool,
+    pub(crate) amount: f64,
+    /// Months between intermediate payments, e.g. 6 for semi-annual ones.
+    pub(crate) months_between: i32,
+    /// Paid together with the last installment.
+    pub(crate) final_balloon: f64,
+}
+
+impl Default for IntermediatePayments {
+    fn default() -> Self {
+        IntermediatePayments {
+            enabled: false,
+            amount: 10_000.0,
+            months_between: 6,
+            final_balloon: 0.0,
+        }
+    }
+}
+
+/// Takes a loan with the house as collateral (`crédito com garantia de
+/// imóvel`) at `month`, counted from the start of the simulation.
+#[derive(Clone)]
+pub(crate) struct HomeEquity {
+    pub(crate) enabled: bool,
+    pub(crate) month: i32,
+    /// Amount requested, limited by `max_loan_to_value`.
+    pub(crate) amount: f64,
+    /// Fraction of the appraised value the bank lends, minus what is still
+    /// owed on the house.
+    pub(crate) max_loan_to_value: f64,
+    pub(crate) monthly_interest: f64,
+    pub(crate) strategy: AmortizationStrategyType,
+    pub(crate) months_to_pay: i32,
+}
+
+impl Default for HomeEquity {
+    fn default() -> Self {
+        HomeEquity {
+            enabled: false,
+            month: 60,
+            amount: 200_000.0,
+            max_loan_to_value: 0.6,
+            monthly_interest: 0.011,
+            strategy: AmortizationStrategyType::Price,
+            months_to_pay: 180,
+        }
+    }
+}
+
+/// Parameters of a house bought under construction (`na planta`), which
+/// precede the amortization of the financing.
+///
+/// Months are counted from 1.
+#[derive(Clone)]
+pub(crate) struct ConstructionPhase {
+    pub(crate) enabled: bool,
+    pub(crate) months_until_delivery: i32,
+    pub(crate) builder_monthly_payment: f64,
+    /// Intermediate payment (`balão`) made every few months to the builder.
+    pub(crate) intermediate_payment: f64,
+    pub(crate) months_between_intermediate_payments: i32,
+    /// Monthly INCC, which corrects the payments to the builder.
+    pub(crate) incc: f64,
+    pub(crate) financing_signed_month: i32,
+    /// Rent paid until the delivery of the keys, corrected by inflation.
+    pub(crate) monthly_rent: f64,
+}
+
+impl Default for ConstructionPhase {
+    fn default() -> Self {
+        ConstructionPhase {
+            enabled: false,
+            months_until_delivery: 36,
+            builder_monthly_payment: 2_000.0,
+            intermediate_payment: 20_000.0,
+            months_between_intermediate_payments: 12,
+            incc: 0.005,
+            financing_signed_month: 1,
+            monthly_rent: 3_000.0,
+        }
+    }
+}
+
+/// Inputs of the reverse calculator, which goes from the installment back to
+/// the term, the rate or the value of the loan.
+pub(crate) struct ReverseCalculator {
+    pub(crate) value: f64,
+    pub(crate) installment: f64,
+    pub(crate) monthly_interest: f64,
+    pub(crate) months_to_pay: i32,
+    pub(crate) strategy: AmortizationStrategyType,
+}
+
+impl Default for ReverseCalculator {
+    fn default() -> Self {
+        ReverseCalculator {
+            value: 450_000.0,
+            installment: 6_000.0,
+            monthly_interest: 0.01,
+            months_to_pay: 360,
+            strategy: AmortizationStrategyType::Price,
         }
     }
 }
@@ -41,6 +763,9 @@ impl Default for House {
 pub(crate) struct Simulation {
     pub(crate) months_to_forecast: i32,
     pub(crate) inflation: f64,
+    /// Calendar year and month, from 1 to 12, of the first simulated month.
+    pub(crate) start_year: i32,
+    pub(crate) start_month: i32,
 }
 
 impl Default for Simulation {
@@ -48,6 +773,18 @@ impl Default for Simulation {
         Simulation {
             months_to_forecast: 120,
             inflation: 0.004,
+            start_year: 2026,
+            start_month: 1,
         }
     }
 }
+
+impl Simulation {
+    /// Gets the calendar year and month, from 1 to 12, of the given month of
+    /// the simulation, counted from 0.
+    pub(crate) fn date_at(&self, month_index: i32) -> (i32, i32) {
+        let months = self.start_year * 12 + self.start_month - 1 + month_index;
+
+        (months.div_euclid(12), months.rem_euclid(12) + 1)
+    }
+}
@@ -1,3 +1,5 @@
+use crate::model::Simulation;
+
 pub(crate) fn format_y_axis(
     grid_mark: egui_plot::GridMark,
     _range: &std::ops::RangeInclusive<f64>,
@@ -5,6 +7,49 @@ pub(crate) fn format_y_axis(
     format_with_thousands_separator(grid_mark.value)
 }
 
+const MONTH_NAMES: [&str; 12] = [
+    "janeiro",
+    "fevereiro",
+    "março",
+    "abril",
+    "maio",
+    "junho",
+    "julho",
+    "agosto",
+    "setembro",
+    "outubro",
+    "novembro",
+    "dezembro",
+];
+
+/// Formats a calendar month, from 1 to 12, as in "março/2041".
+pub(crate) fn format_month(year: i32, month: i32) -> String {
+    format!(
+        "{}/{}",
+        MONTH_NAMES[(month - 1).rem_euclid(12) as usize],
+        year
+    )
+}
+
+/// Formats the x axis, in months of the simulation, as dates such as
+/// "mar/2041".
+pub(crate) fn format_x_axis(
+    simulation: &Simulation,
+) -> impl Fn(egui_plot::GridMark, &std::ops::RangeInclusive<f64>) -> String {
+    let (start_year, start_month) = (simulation.start_year, simulation.start_month);
+
+    move |grid_mark, _range| {
+        if grid_mark.value.fract() != 0.0 {
+            return String::new();
+        }
+
+        let months = start_year * 12 + start_month - 1 + grid_mark.value as i32;
+        let name = MONTH_NAMES[months.rem_euclid(12) as usize];
+
+        format!("{}/{}", &name[..3], months.div_euclid(12))
+    }
+}
+
 pub(crate) fn format_with_thousands_separator(num: f64) -> String {
     let abs = num.abs();
     let sign = if num < 0.0 { "-" } else { "" };
@@ -27,9 +72,27 @@ pub(crate) fn format_with_thousands_separator(num: f64) -> String {
     format!("{}R$ {}", sign, string)
 }
 
+/// Accumulates each series over the ones before it, so that they can be
+/// drawn stacked. Shorter series count as zero after their end.
+pub(crate) fn stack_series(series: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let len = series.iter().map(Vec::len).max().unwrap_or(0);
+    let mut running = vec![0.0; len];
+
+    series
+        .iter()
+        .map(|values| {
+            for (total, value) in running.iter_mut().zip(values) {
+                *total += value;
+            }
+
+            running.clone()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::format_with_thousands_separator;
+    use super::{format_month, format_with_thousands_separator, stack_series};
 
     #[test]
     fn test_formatting() {
@@ -48,4 +111,19 @@ mod tests {
         let number = 1_200_300.1;
         assert_eq!(format_with_thousands_separator(number), "1,200,300.10");
     }
+
+    #[test]
+    fn test_format_month() {
+        assert_eq!(format_month(2041, 3), "março/2041");
+        assert_eq!(format_month(2026, 12), "dezembro/2026");
+    }
+
+    #[test]
+    fn test_stack_series() {
+        let stacked = stack_series(&[vec![1.0, 2.0, 3.0], vec![10.0, 20.0], vec![5.0, 5.0, 5.0]]);
+
+        assert_eq!(stacked[0], vec![1.0, 2.0, 3.0]);
+        assert_eq!(stacked[1], vec![11.0, 22.0, 3.0]);
+        assert_eq!(stacked[2], vec![16.0, 27.0, 8.0]);
+    }
 }
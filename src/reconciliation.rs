@@ -0,0 +1,266 @@
+use crate::calculation::LoanSchedule;
+
+/// Difference, in reais, above which the bank's installment or balance is
+/// considered to diverge from ours.
+const DIVERGENCE_TOLERANCE: f64 = 1.0;
+
+/// One line of the schedule handed by the bank.
+#[derive(Debug, PartialEq)]
+pub(crate) struct BankScheduleRow {
+    /// Number of the installment, counted from 1.
+    pub(crate) month: i32,
+    pub(crate) installment: f64,
+    /// Balance after the installment is paid.
+    pub(crate) balance: f64,
+}
+
+/// One month of the bank's schedule next to our own computation.
+pub(crate) struct ReconciledRow {
+    pub(crate) month: i32,
+    pub(crate) bank_installment: f64,
+    pub(crate) our_installment: f64,
+    pub(crate) bank_balance: f64,
+    pub(crate) our_balance: f64,
+    pub(crate) diverges: bool,
+}
+
+/// What the bank charges, fitted over its schedule, against our own.
+pub(crate) struct ReconciliationReport {
+    pub(crate) rows: Vec<ReconciledRow>,
+    /// Monthly rate the bank charges over the balance. Insurance over the
+    /// balance (`MIP`) and TR are folded into it.
+    pub(crate) fitted_rate: f64,
+    /// Part of `fitted_rate` above the contracted rate.
+    pub(crate) insurance_rate: f64,
+    /// Fixed charges of every month, such as `DFI` and administration fees.
+    pub(crate) monthly_fees: f64,
+}
+
+impl ReconciliationReport {
+    pub(crate) fn divergent_months(&self) -> usize {
+        self.rows.iter().filter(|row| row.diverges).count()
+    }
+}
+
+/// Parses a schedule pasted as CSV, with the month, the installment and the
+/// balance on each line.
+///
+/// Both `,` and `;` separate columns, and values may be written as
+/// `R$ 1.234,56`. Lines which can't be parsed, like headers, are skipped.
+pub(crate) fn parse_bank_schedule(csv: &str) -> Vec<BankScheduleRow> {
+    csv.lines()
+        .filter_map(|line| {
+            let separator = if line.contains(';') { ';' } else { ',' };
+            let columns: Vec<&str> = line.split(separator).map(str::trim).collect();
+
+            match columns[..] {
+                [month, installment, balance, ..] => Some(BankScheduleRow {
+                    month: month.parse().ok()?,
+                    installment: parse_amount(installment)?,
+                    balance: parse_amount(balance)?,
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Parses an amount in either `1234.56` or Brazilian `1.234,56` notation.
+///
+/// Without a `,`, dots each followed by exactly three digits, as in `9.000`,
+/// are taken as thousands separators.
+fn parse_amount(text: &str) -> Option<f64> {
+    let text = text.trim_start_matches("R$").trim();
+    let groups: Vec<&str> = text.split('.').collect();
+    let has_thousands_separators = groups.len() > 1
+        && groups[1..]
+            .iter()
+            .all(|group| group.len() == 3 && group.bytes().all(|byte| byte.is_ascii_digit()));
+
+    let normalized = if text.contains(',') || has_thousands_separators {
+        text.replace('.', "").replace(',', ".")
+    } else {
+        text.to_string()
+    };
+
+    normalized.parse().ok()
+}
+
+/// Fits the rate and the fixed charges behind the bank's installments and
+/// compares them, month by month, with our own schedule.
+///
+/// The amortization of each month is the drop of the balance, so whatever
+/// else is paid is modeled as a rate over the previous balance plus fixed
+/// fees, fitted by least squares. Returns `None` if there are not enough
+/// months to fit.
+pub(crate) fn reconcile(
+    rows: &[BankScheduleRow],
+    loan: &LoanSchedule,
+    contracted_rate: f64,
+) -> Option<ReconciliationReport> {
+    if rows.len() < 2 {
+        return None;
+    }
+
+    let our_balance = |months_paid: i32| -> f64 {
+        loan.balances
+            .get(months_paid.max(0) as usize)
+            .or(loan.balances.last())
+            .copied()
+            .unwrap_or(0.0)
+    };
+
+    // The balance before each installment, from the bank's previous line
+    // when there is one.
+    let previous_balances: Vec<f64> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| match i.checked_sub(1).map(|j| &rows[j]) {
+            Some(previous) if previous.month == row.month - 1 => previous.balance,
+            _ => our_balance(row.month - 1),
+        })
+        .collect();
+
+    // What is paid on top of the amortization.
+    let charges: Vec<f64> = rows
+        .iter()
+        .zip(&previous_balances)
+        .map(|(row, previous)| row.installment - (previous - row.balance))
+        .collect();
+
+    let n = rows.len() as f64;
+    let mean_balance = previous_balances.iter().sum::<f64>() / n;
+    let mean_charge = charges.iter().sum::<f64>() / n;
+
+    let variance: f64 = previous_balances
+        .iter()
+        .map(|balance| (balance - mean_balance).powi(2))
+        .sum();
+
+    if variance <= 0.0 {
+        return None;
+    }
+
+    let covariance: f64 = previous_balances
+        .iter()
+        .zip(&charges)
+        .map(|(balance, charge)| (balance - mean_balance) * (charge - mean_charge))
+        .sum();
+
+    let fitted_rate = covariance / variance;
+    let monthly_fees = mean_charge - fitted_rate * mean_balance;
+
+    let rows = rows
+        .iter()
+        .map(|row| {
+            let months_paid = row.month - 1;
+            let our_installment = loan
+                .monthly_payments
+                .get(months_paid.max(0) as usize)
+                .copied()
+                .unwrap_or(0.0);
+            let our_balance = our_balance(row.month);
+
+            ReconciledRow {
+                month: row.month,
+                bank_installment: row.installment,
+                our_installment,
+                bank_balance: row.balance,
+                our_balance,
+                diverges: (row.installment - our_installment).abs() > DIVERGENCE_TOLERANCE
+                    || (row.balance - our_balance).abs() > DIVERGENCE_TOLERANCE,
+            }
+        })
+        .collect();
+
+    Some(ReconciliationReport {
+        rows,
+        fitted_rate,
+        insurance_rate: fitted_rate - contracted_rate,
+        monthly_fees,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bank_schedule() {
+        let csv = "Parcela;Valor;Saldo\n\
+                   1;R$ 1.100,00;9.000,00\n\
+                   2, 1090.50, 8000\n\
+                   \n\
+                   3;sem valor;7.000,00";
+
+        assert_eq!(
+            parse_bank_schedule(csv),
+            vec![
+                BankScheduleRow {
+                    month: 1,
+                    installment: 1_100.0,
+                    balance: 9_000.0,
+                },
+                BankScheduleRow {
+                    month: 2,
+                    installment: 1_090.5,
+                    balance: 8_000.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("9.000"), Some(9_000.0));
+        assert_eq!(parse_amount("R$ 1.234.567"), Some(1_234_567.0));
+        assert_eq!(parse_amount("1.234,56"), Some(1_234.56));
+        assert_eq!(parse_amount("1090.50"), Some(1_090.5));
+        assert_eq!(parse_amount("8000"), Some(8_000.0));
+    }
+
+    #[test]
+    fn test_reconcile() {
+        // SAC of 10,000 at 1% in 10 months.
+        let loan = LoanSchedule {
+            monthly_payments: (0..10).map(|i| 1_000.0 + (10 - i) as f64 * 10.0).collect(),
+            balances: (0..=10).map(|i| (10 - i) as f64 * 1_000.0).collect(),
+            ..Default::default()
+        };
+
+        // The bank charges 0.05% of insurance over the balance and 25 of
+        // fees, and skips the amortization on month 4.
+        let rows: Vec<BankScheduleRow> = (1..=5)
+            .map(|month| {
+                let previous = (11 - month) as f64 * 1_000.0;
+                let amortization = if month == 4 { 0.0 } else { 1_000.0 };
+                let previous = if month == 5 {
+                    previous + 1_000.0
+                } else {
+                    previous
+                };
+
+                BankScheduleRow {
+                    month,
+                    installment: amortization + previous * 0.0105 + 25.0,
+                    balance: previous - amortization,
+                }
+            })
+            .collect();
+
+        let report = reconcile(&rows, &loan, 0.01).unwrap();
+
+        assert!((report.fitted_rate - 0.0105).abs() < 1e-9);
+        assert!((report.insurance_rate - 0.0005).abs() < 1e-9);
+        assert!((report.monthly_fees - 25.0).abs() < 1e-6);
+
+        // Every installment carries the insurance and fees, which are above
+        // the tolerance.
+        assert_eq!(report.divergent_months(), 5);
+        assert!((report.rows[0].our_installment - 1_100.0).abs() < 1e-9);
+        assert_eq!(report.rows[3].bank_balance, 7_000.0);
+        assert_eq!(report.rows[3].our_balance, 6_000.0);
+
+        assert!(reconcile(&rows[..1], &loan, 0.01).is_none());
+    }
+}
@@ -1,28 +1,378 @@
 use egui::{Color32, Grid, Ui};
-use egui_plot::{Legend, Line, PlotPoints};
+use egui_plot::{Legend, Line, LineStyle, PlotPoints};
 
 use crate::{
-    calculation::SimulationOutput,
+    calculation::{
+        calculate_loan_schedule, convert_monthly_interest, solve_rate, solve_term, solve_value,
+        LoanSchedule, ReserveReport, RetirementReport, SimulationOutput,
+    },
     format_with_thousands_separator,
-    model::{Buyer, House, Simulation},
-    plotting, AmortizationStrategyType, PlotSelection,
+    mcmv::Assessment,
+    model::{
+        AccountMode, AdditionalLoan, AdjustableRate, Buyer, CashFlowFrequency, CashFlowItem,
+        ConstructionPhase, DayCount, ExpenseCategory, ExpenseGrowth, GracePeriod, GracePeriodMode,
+        HomeEquity, House, Indexation, IntermediatePayments, LoanFees, Mcmv, Offer,
+        PaymentFrequency, Refinancing, Region, Rental, Retirement, ReverseCalculator, RoundingMode,
+        Sale, Simulation, TradeUp, WithdrawalRule,
+    },
+    plotting,
+    reconciliation::{parse_bank_schedule, reconcile},
+    AmortizationStrategyType, OfferRanking, PlotSelection, ValueView,
 };
 
+/// Longest term searched by the reverse calculator.
+const MAX_REVERSE_MONTHS: i32 = 720;
+
 pub(crate) fn render_buyer_params(ui: &mut Ui, buyer: &mut Buyer) {
     ui.heading("Parâmetros do Comprador");
+    render_buyer_profile(ui, buyer);
+    ui.add(
+        egui::Slider::new(&mut buyer.overdraft_monthly_interest, 0.0..=1.0)
+            .text("Juros do Cheque Especial"),
+    );
+    ui.add(
+        egui::Slider::new(&mut buyer.minimum_reserve_months, 0.0..=24.0)
+            .text("Reserva Mínima (meses)"),
+    );
+}
+
+/// Renders what each buyer has on their own, as opposed to the household.
+fn render_buyer_profile(ui: &mut Ui, buyer: &mut Buyer) {
+    ui.horizontal(|ui| {
+        ui.label("Nome:");
+        ui.add(egui::TextEdit::singleline(&mut buyer.name).desired_width(120.0));
+    });
+    ui.add(egui::Slider::new(&mut buyer.age, 18..=80).text("Idade"));
     ui.add(
         egui::Slider::new(&mut buyer.starting_money, 0.0..=2_000_000.0).text("Dinheiro Inicial"),
     );
     ui.add(egui::Slider::new(&mut buyer.liquid_salary, 0.0..=100_000.0).text("Salário Líquido"));
+    ui.add(egui::Slider::new(&mut buyer.gross_salary, 0.0..=150_000.0).text("Salário Bruto"));
     ui.add(
         egui::Slider::new(&mut buyer.fixed_monthly_expenses, 0.0..=100_000.0)
-            .text("Gastos Mensais"),
+            .text("Gastos Mensais Gerais"),
     );
     ui.add(egui::Slider::new(&mut buyer.yearly_bonus, 0.0..=2_000_000.0).text("Bônus Anual"));
+    ui.add(egui::Slider::new(&mut buyer.bonus_month, 1..=12).text("Mês do Bônus"));
+    ui.checkbox(&mut buyer.thirteenth_salary, "Recebe 13º salário");
     ui.add(
         egui::Slider::new(&mut buyer.investment_monthly_interest, 0.0..=1.0)
             .text("Taxa de Lucro em Investimentos"),
     );
+    ui.add(egui::Slider::new(&mut buyer.fgts_balance, 0.0..=1_000_000.0).text("Saldo do FGTS"));
+    ui.add(
+        egui::Slider::new(&mut buyer.fgts_monthly_deposit, 0.0..=20_000.0)
+            .text("Depósito Mensal do FGTS"),
+    );
+    render_retirement_params(ui, &mut buyer.retirement);
+}
+
+fn render_retirement_params(ui: &mut Ui, retirement: &mut Retirement) {
+    ui.collapsing("Aposentadoria", |ui| {
+        ui.checkbox(&mut retirement.enabled, "Aposentar-se");
+        ui.add_enabled_ui(retirement.enabled, |ui| {
+            ui.add(egui::Slider::new(&mut retirement.age, 18..=100).text("Idade de Aposentadoria"));
+            ui.add(
+                egui::Slider::new(&mut retirement.monthly_pension, 0.0..=50_000.0)
+                    .text("Aposentadoria Mensal (INSS, valores de hoje)"),
+            );
+            ui.horizontal(|ui| {
+                ui.label("Limite de saque:");
+                ui.selectable_value(
+                    &mut retirement.withdrawal_rule,
+                    WithdrawalRule::AsNeeded,
+                    "Conforme a necessidade",
+                );
+                ui.selectable_value(
+                    &mut retirement.withdrawal_rule,
+                    WithdrawalRule::FixedRate,
+                    "Taxa fixa corrigida",
+                );
+                ui.selectable_value(
+                    &mut retirement.withdrawal_rule,
+                    WithdrawalRule::PercentOfBalance,
+                    "Taxa sobre o saldo",
+                );
+            });
+            ui.add_enabled(
+                retirement.withdrawal_rule != WithdrawalRule::AsNeeded,
+                egui::Slider::new(&mut retirement.yearly_withdrawal_rate, 0.0..=0.2)
+                    .text("Taxa Anual de Saque"),
+            );
+            if retirement.withdrawal_rule != WithdrawalRule::AsNeeded {
+                ui.label("Os gastos gerais são cortados para o saque caber no limite.");
+            }
+            ui.add(
+                egui::Slider::new(&mut retirement.target_age, 18..=120)
+                    .text("Reserva deve durar até a idade"),
+            );
+        });
+    });
+}
+
+pub(crate) fn render_co_buyers(
+    ui: &mut Ui,
+    co_buyers: &mut Vec<Buyer>,
+    buyer_share: &mut f64,
+    account_mode: &mut AccountMode,
+) {
+    ui.collapsing("Composição de Renda", |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Contas:");
+            ui.selectable_value(account_mode, AccountMode::Combined, "Conjunta");
+            ui.selectable_value(account_mode, AccountMode::Separate, "Separadas");
+        });
+
+        let separate = *account_mode == AccountMode::Separate;
+        if !separate {
+            ui.label("Na conta conjunta, a reserva é avaliada na aposentadoria do comprador.");
+        }
+        ui.add_enabled(
+            separate,
+            egui::Slider::new(buyer_share, 0.0..=1.0).text("Parte das Parcelas do Comprador"),
+        );
+
+        let mut to_remove = None;
+
+        for (i, co_buyer) in co_buyers.iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                ui.collapsing(co_buyer.name.clone(), |ui| {
+                    render_buyer_profile(ui, co_buyer);
+                    ui.add_enabled(
+                        separate,
+                        egui::Slider::new(&mut co_buyer.installment_share, 0.0..=1.0)
+                            .text("Parte das Parcelas"),
+                    );
+
+                    if ui.button("Remover").clicked() {
+                        to_remove = Some(i);
+                    }
+                });
+            });
+        }
+
+        if let Some(i) = to_remove {
+            co_buyers.remove(i);
+        }
+
+        if ui.button("Adicionar").clicked() {
+            co_buyers.push(Buyer::co_buyer());
+        }
+    });
+}
+
+pub(crate) fn render_expense_categories(ui: &mut Ui, categories: &mut Vec<ExpenseCategory>) {
+    ui.collapsing("Gastos por Categoria", |ui| {
+        let mut to_remove = None;
+
+        Grid::new("expense_categories").show(ui, |ui| {
+            for (i, category) in categories.iter_mut().enumerate() {
+                ui.push_id(i, |ui| {
+                    ui.add(egui::TextEdit::singleline(&mut category.name).desired_width(120.0));
+                });
+                ui.add(
+                    egui::DragValue::new(&mut category.monthly_amount)
+                        .range(0.0..=100_000.0)
+                        .prefix("R$ "),
+                );
+                ui.selectable_value(
+                    &mut category.growth,
+                    ExpenseGrowth::InflationPlusSpread,
+                    "Inflação +",
+                );
+                ui.selectable_value(&mut category.growth, ExpenseGrowth::Custom, "Taxa própria");
+                ui.add(
+                    egui::DragValue::new(&mut category.monthly_rate)
+                        .range(-1.0..=1.0)
+                        .speed(0.001)
+                        .suffix(" a.m."),
+                );
+
+                if ui.button("Remover").clicked() {
+                    to_remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(i) = to_remove {
+            categories.remove(i);
+        }
+
+        if ui.button("Adicionar").clicked() {
+            categories.push(ExpenseCategory::default());
+        }
+    });
+}
+
+pub(crate) fn render_cash_flow_items(ui: &mut Ui, cash_flows: &mut Vec<CashFlowItem>) {
+    ui.collapsing("Receitas e Despesas Recorrentes", |ui| {
+        let mut to_remove = None;
+
+        Grid::new("cash_flows").show(ui, |ui| {
+            for (i, item) in cash_flows.iter_mut().enumerate() {
+                ui.push_id(i, |ui| {
+                    ui.add(egui::TextEdit::singleline(&mut item.name).desired_width(120.0));
+                });
+                ui.selectable_value(&mut item.is_income, false, "Despesa");
+                ui.selectable_value(&mut item.is_income, true, "Receita");
+                ui.add(
+                    egui::DragValue::new(&mut item.amount)
+                        .range(0.0..=2_000_000.0)
+                        .prefix("R$ "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut item.start_month)
+                        .range(1..=720)
+                        .prefix("Início: "),
+                );
+
+                let mut has_end = item.end_month.is_some();
+                ui.checkbox(&mut has_end, "Fim:");
+                let mut end_month = item.end_month.unwrap_or(item.start_month);
+                ui.add_enabled(
+                    has_end,
+                    egui::DragValue::new(&mut end_month).range(item.start_month..=720),
+                );
+                item.end_month = has_end.then_some(end_month);
+
+                egui::ComboBox::from_id_salt(("frequency", i))
+                    .selected_text(match item.frequency {
+                        CashFlowFrequency::Monthly => "Mensal",
+                        CashFlowFrequency::Yearly => "Anual",
+                        CashFlowFrequency::OneOff => "Única",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut item.frequency,
+                            CashFlowFrequency::Monthly,
+                            "Mensal",
+                        );
+                        ui.selectable_value(
+                            &mut item.frequency,
+                            CashFlowFrequency::Yearly,
+                            "Anual",
+                        );
+                        ui.selectable_value(
+                            &mut item.frequency,
+                            CashFlowFrequency::OneOff,
+                            "Única",
+                        );
+                    });
+
+                egui::ComboBox::from_id_salt(("indexation", i))
+                    .selected_text(match item.indexation {
+                        Indexation::None => "Sem correção",
+                        Indexation::Inflation => "Inflação",
+                        Indexation::Custom => "Taxa própria",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut item.indexation, Indexation::None, "Sem correção");
+                        ui.selectable_value(
+                            &mut item.indexation,
+                            Indexation::Inflation,
+                            "Inflação",
+                        );
+                        ui.selectable_value(
+                            &mut item.indexation,
+                            Indexation::Custom,
+                            "Taxa própria",
+                        );
+                    });
+                ui.add_enabled(
+                    matches!(item.indexation, Indexation::Custom),
+                    egui::DragValue::new(&mut item.custom_monthly_rate)
+                        .range(0.0..=1.0)
+                        .speed(0.001),
+                );
+
+                if ui.button("Remover").clicked() {
+                    to_remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(i) = to_remove {
+            cash_flows.remove(i);
+        }
+
+        if ui.button("Adicionar").clicked() {
+            cash_flows.push(CashFlowItem::default());
+        }
+    });
+}
+
+pub(crate) fn render_additional_loans(ui: &mut Ui, additional_loans: &mut Vec<AdditionalLoan>) {
+    ui.collapsing("Outros Imóveis e Financiamentos", |ui| {
+        let mut to_remove = None;
+
+        for (i, additional) in additional_loans.iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                ui.group(|ui| {
+                    let house = &mut additional.house;
+
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut house.name).desired_width(120.0));
+                        ui.checkbox(&mut additional.is_property, "Imóvel");
+
+                        if ui.button("Remover").clicked() {
+                            to_remove = Some(i);
+                        }
+                    });
+                    ui.add(
+                        egui::Slider::new(&mut house.house_price, 0.0..=2_000_000.0)
+                            .text("Preço Total"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut house.down_payment, 0.0..=2_000_000.0)
+                            .text("Entrada"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut additional.purchased_after, 0..=720)
+                            .text("Meses até a Compra"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut house.months_to_pay, 1..=420)
+                            .text("Número de Parcelas"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut house.house_monthly_interest, 0.0..=1.0)
+                            .text("Juros Mensal"),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.label("Tabela:");
+                        ui.selectable_value(
+                            &mut additional.strategy,
+                            AmortizationStrategyType::Sac,
+                            "Tabela SAC",
+                        );
+                        ui.selectable_value(
+                            &mut additional.strategy,
+                            AmortizationStrategyType::Price,
+                            "Tabela PRICE",
+                        );
+                    });
+
+                    if additional.is_property {
+                        ui.add(
+                            egui::Slider::new(&mut house.monthly_appreciation, -0.1..=0.1)
+                                .text("Valorização Mensal do Imóvel"),
+                        );
+                        render_rental_params(ui, &mut house.rental);
+                    }
+                });
+            });
+        }
+
+        if let Some(i) = to_remove {
+            additional_loans.remove(i);
+        }
+
+        if ui.button("Adicionar").clicked() {
+            additional_loans.push(AdditionalLoan::default());
+        }
+    });
 }
 
 pub(crate) fn render_house_params(
@@ -42,6 +392,689 @@ pub(crate) fn render_house_params(
             .text("Amortização Extra Anual"),
     );
     ui.add(egui::Slider::new(&mut house.house_monthly_interest, 0.0..=1.0).text("Juros Mensal"));
+    ui.add(
+        egui::Slider::new(&mut house.monthly_appreciation, -0.1..=0.1)
+            .text("Valorização Mensal do Imóvel"),
+    );
+    ui.add(egui::Slider::new(&mut house.fees.upfront, 0.0..=50_000.0).text("Tarifas Iniciais"));
+    ui.add(
+        egui::Slider::new(&mut house.fees.monthly_fee, 0.0..=500.0).text("Taxa de Administração"),
+    );
+    ui.checkbox(
+        &mut house.fees.insurance_by_age,
+        "MIP pela idade dos compradores",
+    );
+    ui.add_enabled(
+        !house.fees.insurance_by_age,
+        egui::Slider::new(&mut house.fees.monthly_insurance_rate, 0.0..=0.01)
+            .text("Seguro Mensal sobre o Saldo"),
+    );
+    ui.horizontal(|ui| {
+        ui.label("Arredondamento:");
+        ui.selectable_value(&mut house.rounding, RoundingMode::None, "Nenhum");
+        ui.selectable_value(
+            &mut house.rounding,
+            RoundingMode::HalfUp,
+            "Centavos (half-up)",
+        );
+        ui.selectable_value(
+            &mut house.rounding,
+            RoundingMode::HalfEven,
+            "Centavos (half-even)",
+        );
+    });
+    ui.horizontal(|ui| {
+        ui.label("Parcelas:");
+        ui.selectable_value(
+            &mut house.payment_frequency,
+            PaymentFrequency::Monthly,
+            "Mensais",
+        );
+        ui.selectable_value(
+            &mut house.payment_frequency,
+            PaymentFrequency::Biweekly,
+            "Quinzenais",
+        );
+        ui.selectable_value(
+            &mut house.payment_frequency,
+            PaymentFrequency::Weekly,
+            "Semanais",
+        );
+    });
+    ui.add_enabled_ui(house.payment_frequency != PaymentFrequency::Monthly, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Contagem de dias:");
+            ui.selectable_value(&mut house.day_count, DayCount::Thirty360, "30/360");
+            ui.selectable_value(&mut house.day_count, DayCount::Actual365, "Corridos/365");
+            ui.selectable_value(&mut house.day_count, DayCount::Business252, "Úteis/252");
+        });
+        ui.label(format!(
+            "Juros por parcela: {:.4}%",
+            convert_monthly_interest(
+                house.house_monthly_interest,
+                house.payment_frequency,
+                house.day_count
+            ) * 100.0
+        ));
+    });
+}
+
+pub(crate) fn render_grace_period_params(ui: &mut Ui, grace_period: &mut GracePeriod) {
+    ui.collapsing("Carência", |ui| {
+        ui.checkbox(&mut grace_period.enabled, "Começar com carência");
+        ui.add_enabled_ui(grace_period.enabled, |ui| {
+            ui.add(egui::Slider::new(&mut grace_period.months, 1..=60).text("Meses de Carência"));
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut grace_period.mode,
+                    GracePeriodMode::InterestOnly,
+                    "Pagar só os juros",
+                );
+                ui.selectable_value(
+                    &mut grace_period.mode,
+                    GracePeriodMode::Capitalized,
+                    "Capitalizar os juros",
+                );
+            });
+        });
+    });
+}
+
+pub(crate) fn render_intermediate_payments_params(
+    ui: &mut Ui,
+    intermediate_payments: &mut IntermediatePayments,
+) {
+    ui.collapsing("Parcelas Intermediárias", |ui| {
+        ui.checkbox(
+            &mut intermediate_payments.enabled,
+            "Pagar parcelas intermediárias",
+        );
+        ui.add_enabled_ui(intermediate_payments.enabled, |ui| {
+            ui.add(
+                egui::Slider::new(&mut intermediate_payments.amount, 0.0..=500_000.0)
+                    .text("Valor da Intermediária"),
+            );
+            ui.add(
+                egui::Slider::new(&mut intermediate_payments.months_between, 1..=24)
+                    .text("Meses entre Intermediárias"),
+            );
+            ui.add(
+                egui::Slider::new(&mut intermediate_payments.final_balloon, 0.0..=2_000_000.0)
+                    .text("Parcela Final (Balão)"),
+            );
+        });
+    });
+}
+
+pub(crate) fn render_sale_params(ui: &mut Ui, sale: &mut Sale) {
+    ui.collapsing("Venda do Imóvel", |ui| {
+        ui.checkbox(&mut sale.enabled, "Vender o imóvel");
+        ui.add_enabled_ui(sale.enabled, |ui| {
+            ui.add(egui::Slider::new(&mut sale.month, 1..=720).text("Mês da Venda"));
+            ui.add(
+                egui::Slider::new(&mut sale.broker_commission, 0.0..=0.2)
+                    .text("Comissão do Corretor"),
+            );
+            ui.add(
+                egui::Slider::new(&mut sale.reinvested_fraction, 0.0..=1.0)
+                    .text("Fração Reinvestida em Imóvel (180 dias)"),
+            );
+        });
+    });
+}
+
+pub(crate) fn render_trade_up_params(ui: &mut Ui, trade_up: &mut TradeUp, sale: &Sale) {
+    ui.collapsing("Troca por Imóvel Maior", |ui| {
+        ui.add_enabled(
+            sale.enabled,
+            egui::Checkbox::new(&mut trade_up.enabled, "Comprar outro imóvel com a venda"),
+        );
+        ui.add_enabled_ui(sale.enabled && trade_up.enabled, |ui| {
+            let house = &mut trade_up.house;
+
+            ui.add(
+                egui::Slider::new(&mut house.house_price, 0.0..=5_000_000.0)
+                    .text("Preço do Novo Imóvel"),
+            );
+            ui.add(
+                egui::Slider::new(&mut house.down_payment, 0.0..=2_000_000.0)
+                    .text("Entrada Extra (além da venda)"),
+            );
+            ui.add(egui::Slider::new(&mut house.months_to_pay, 1..=420).text("Número de Parcelas"));
+            ui.add(
+                egui::Slider::new(&mut house.house_monthly_interest, 0.0..=1.0)
+                    .text("Juros Mensal"),
+            );
+            ui.add(
+                egui::Slider::new(&mut house.monthly_appreciation, -0.1..=0.1)
+                    .text("Valorização Mensal do Imóvel"),
+            );
+            ui.horizontal(|ui| {
+                ui.label("Tabela:");
+                ui.selectable_value(
+                    &mut trade_up.strategy,
+                    AmortizationStrategyType::Sac,
+                    "Tabela SAC",
+                );
+                ui.selectable_value(
+                    &mut trade_up.strategy,
+                    AmortizationStrategyType::Price,
+                    "Tabela PRICE",
+                );
+            });
+        });
+    });
+}
+
+pub(crate) fn render_rental_params(ui: &mut Ui, rental: &mut Rental) {
+    ui.collapsing("Aluguel do Imóvel", |ui| {
+        ui.checkbox(&mut rental.enabled, "Alugar o imóvel");
+        ui.add_enabled_ui(rental.enabled, |ui| {
+            ui.add(egui::Slider::new(&mut rental.start_month, 1..=720).text("Mês de Início"));
+            ui.add(
+                egui::Slider::new(&mut rental.monthly_rent, 0.0..=100_000.0).text("Aluguel Mensal"),
+            );
+            ui.add(
+                egui::Slider::new(&mut rental.yearly_adjustment, 0.0..=1.0).text("Reajuste Anual"),
+            );
+            ui.add(egui::Slider::new(&mut rental.vacancy_rate, 0.0..=1.0).text("Vacância"));
+            ui.add(
+                egui::Slider::new(&mut rental.agency_fee, 0.0..=1.0).text("Taxa da Imobiliária"),
+            );
+        });
+    });
+}
+
+pub(crate) fn render_refinancing_params(ui: &mut Ui, refinancing: &mut Refinancing) {
+    ui.collapsing("Portabilidade / Refinanciamento", |ui| {
+        ui.checkbox(&mut refinancing.enabled, "Refinanciar");
+        ui.add_enabled_ui(refinancing.enabled, |ui| {
+            ui.add(egui::Slider::new(&mut refinancing.month, 1..=720).text("Mês da Portabilidade"));
+            ui.add(
+                egui::Slider::new(&mut refinancing.monthly_interest, 0.0..=1.0)
+                    .text("Novo Juros Mensal"),
+            );
+            ui.add(
+                egui::Slider::new(&mut refinancing.months_to_pay, 1..=360)
+                    .text("Novo Número de Parcelas"),
+            );
+            ui.add(
+                egui::Slider::new(&mut refinancing.switching_costs, 0.0..=200_000.0)
+                    .text("Custos da Troca"),
+            );
+            ui.horizontal(|ui| {
+                ui.label("Nova Tabela:");
+                ui.selectable_value(
+                    &mut refinancing.strategy,
+                    AmortizationStrategyType::Sac,
+                    "Tabela SAC",
+                );
+                ui.selectable_value(
+                    &mut refinancing.strategy,
+                    AmortizationStrategyType::Price,
+                    "Tabela PRICE",
+                );
+            });
+        });
+    });
+}
+
+pub(crate) fn render_mcmv_params(ui: &mut Ui, program: &mut Mcmv, assessment: &Assessment) {
+    ui.collapsing("Minha Casa Minha Vida", |ui| {
+        ui.checkbox(&mut program.enabled, "Financiar pelo programa");
+        ui.add_enabled_ui(program.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Região:");
+                ui.selectable_value(
+                    &mut program.region,
+                    Region::NorthNortheast,
+                    "Norte/Nordeste",
+                );
+                ui.selectable_value(&mut program.region, Region::Other, "Demais Regiões");
+            });
+
+            if let Some(band) = assessment.band {
+                ui.label("Pela renda bruta somada dos compradores:");
+                ui.label(format!(
+                    "{}: juros de {:.2}% a.a., imóvel até {}",
+                    band.name,
+                    ((1.0 + assessment.monthly_interest).powi(12) - 1.0) * 100.0,
+                    format_with_thousands_separator(band.max_property_value)
+                ));
+                ui.label(format!(
+                    "Subsídio máximo da faixa: {}",
+                    format_with_thousands_separator(band.max_subsidy)
+                ));
+
+                ui.horizontal(|ui| {
+                    let mut has_subsidy = program.subsidy.is_some();
+                    ui.checkbox(&mut has_subsidy, "Subsídio concedido:");
+                    let mut subsidy = program.subsidy.unwrap_or(band.max_subsidy);
+                    ui.add_enabled(
+                        has_subsidy,
+                        egui::DragValue::new(&mut subsidy)
+                            .range(0.0..=band.max_subsidy)
+                            .speed(100.0),
+                    );
+                    program.subsidy = has_subsidy.then_some(subsidy);
+                });
+                ui.label(format!(
+                    "Subsídio: {}",
+                    format_with_thousands_separator(assessment.subsidy)
+                ));
+            }
+        });
+    });
+}
+
+/// Warns when the house falls outside the program it is financed by.
+pub(crate) fn render_mcmv_warnings(ui: &mut Ui, assessment: &Assessment) {
+    for warning in &assessment.warnings {
+        ui.colored_label(
+            Color32::from_rgb(230, 140, 0),
+            format!("Minha Casa Minha Vida: {}", warning),
+        );
+    }
+
+    if !assessment.eligible {
+        ui.colored_label(Color32::from_rgb(230, 140, 0), "Simulando sem o programa.");
+    }
+}
+
+pub(crate) fn render_escrow_params(ui: &mut Ui, fees: &mut LoanFees) {
+    ui.collapsing("PMI e Escrow (EUA)", |ui| {
+        ui.add(
+            egui::Slider::new(&mut fees.pmi_monthly_rate, 0.0..=0.002)
+                .text("PMI Mensal sobre o Financiado"),
+        );
+        ui.add_enabled(
+            fees.pmi_monthly_rate > 0.0,
+            egui::Slider::new(&mut fees.pmi_max_loan_to_value, 0.0..=1.0)
+                .text("LTV para Retirar o PMI"),
+        );
+        ui.add(
+            egui::Slider::new(&mut fees.monthly_property_tax_rate, 0.0..=0.005)
+                .text("Property Tax Mensal sobre o Valor"),
+        );
+        ui.add(
+            egui::Slider::new(&mut fees.monthly_homeowners_insurance, 0.0..=2_000.0)
+                .text("Seguro Residencial Mensal"),
+        );
+    });
+}
+
+pub(crate) fn render_adjustable_rate_params(ui: &mut Ui, adjustable_rate: &mut AdjustableRate) {
+    ui.collapsing("Taxa Variável (ARM / Euribor)", |ui| {
+        ui.checkbox(
+            &mut adjustable_rate.enabled,
+            "Reajustar a taxa por um índice",
+        );
+        ui.add_enabled_ui(adjustable_rate.enabled, |ui| {
+            ui.add(
+                egui::Slider::new(&mut adjustable_rate.fixed_months, 1..=120)
+                    .text("Meses com Taxa Inicial"),
+            );
+            ui.add(
+                egui::Slider::new(&mut adjustable_rate.reset_months, 1..=60)
+                    .text("Meses entre Reajustes"),
+            );
+            ui.add(
+                egui::Slider::new(&mut adjustable_rate.margin, 0.0..=0.02).text("Margem Mensal"),
+            );
+            ui.add(
+                egui::Slider::new(&mut adjustable_rate.periodic_cap, 0.0..=0.02)
+                    .text("Teto por Reajuste"),
+            );
+            ui.add(
+                egui::Slider::new(&mut adjustable_rate.lifetime_cap, 0.0..=0.05)
+                    .text("Teto sobre a Taxa Inicial"),
+            );
+            ui.add(egui::Slider::new(&mut adjustable_rate.floor, 0.0..=0.02).text("Piso"));
+
+            ui.label("Índice mensal em cada reajuste:");
+            let mut to_remove = None;
+
+            Grid::new("index_path").show(ui, |ui| {
+                for (i, index) in adjustable_rate.index_path.iter_mut().enumerate() {
+                    ui.label(format!("{}º", i + 1));
+                    ui.add(
+                        egui::DragValue::new(index)
+                            .range(-0.01..=0.05)
+                            .speed(0.0001),
+                    );
+
+                    if ui.button("Remover").clicked() {
+                        to_remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+
+            if let Some(i) = to_remove {
+                adjustable_rate.index_path.remove(i);
+            }
+
+            if ui.button("Adicionar").clicked() {
+                let last = adjustable_rate.index_path.last().copied().unwrap_or(0.0);
+                adjustable_rate.index_path.push(last);
+            }
+        });
+    });
+}
+
+pub(crate) fn render_home_equity_params(ui: &mut Ui, home_equity: &mut HomeEquity) {
+    ui.collapsing("Home Equity", |ui| {
+        ui.checkbox(
+            &mut home_equity.enabled,
+            "Tomar crédito com garantia do imóvel",
+        );
+        ui.add_enabled_ui(home_equity.enabled, |ui| {
+            ui.add(egui::Slider::new(&mut home_equity.month, 1..=720).text("Mês do Crédito"));
+            ui.add(
+                egui::Slider::new(&mut home_equity.amount, 0.0..=2_000_000.0).text("Valor Pedido"),
+            );
+            ui.add(
+                egui::Slider::new(&mut home_equity.max_loan_to_value, 0.0..=1.0)
+                    .text("Limite sobre a Avaliação"),
+            );
+            ui.add(
+                egui::Slider::new(&mut home_equity.monthly_interest, 0.0..=1.0)
+                    .text("Juros Mensal"),
+            );
+            ui.add(
+                egui::Slider::new(&mut home_equity.months_to_pay, 1..=240)
+                    .text("Número de Parcelas"),
+            );
+            ui.horizontal(|ui| {
+                ui.label("Tabela:");
+                ui.selectable_value(
+                    &mut home_equity.strategy,
+                    AmortizationStrategyType::Sac,
+                    "Tabela SAC",
+                );
+                ui.selectable_value(
+                    &mut home_equity.strategy,
+                    AmortizationStrategyType::Price,
+                    "Tabela PRICE",
+                );
+            });
+        });
+    });
+}
+
+pub(crate) fn render_construction_params(ui: &mut Ui, construction: &mut ConstructionPhase) {
+    ui.collapsing("Compra na Planta", |ui| {
+        ui.checkbox(&mut construction.enabled, "Imóvel em construção");
+        ui.add_enabled_ui(construction.enabled, |ui| {
+            ui.add(
+                egui::Slider::new(&mut construction.months_until_delivery, 1..=120)
+                    .text("Meses até a Entrega das Chaves"),
+            );
+            ui.add(
+                egui::Slider::new(&mut construction.builder_monthly_payment, 0.0..=100_000.0)
+                    .text("Parcela Mensal à Construtora"),
+            );
+            ui.add(
+                egui::Slider::new(&mut construction.intermediate_payment, 0.0..=1_000_000.0)
+                    .text("Parcela Intermediária (Balão)"),
+            );
+            ui.add(
+                egui::Slider::new(
+                    &mut construction.months_between_intermediate_payments,
+                    0..=36,
+                )
+                .text("Meses entre Balões"),
+            );
+            ui.add(egui::Slider::new(&mut construction.incc, 0.0..=0.1).text("INCC Mensal"));
+            ui.add(
+                egui::Slider::new(
+                    &mut construction.financing_signed_month,
+                    1..=construction.months_until_delivery,
+                )
+                .text("Mês de Assinatura do Financiamento"),
+            );
+            ui.add(
+                egui::Slider::new(&mut construction.monthly_rent, 0.0..=50_000.0)
+                    .text("Aluguel até a Entrega"),
+            );
+        });
+    });
+}
+
+pub(crate) fn render_bank_schedule_reconciliation(
+    ui: &mut Ui,
+    bank_schedule: &mut String,
+    house: &House,
+    strategy: AmortizationStrategyType,
+) {
+    ui.collapsing("Conferir Cronograma do Banco", |ui| {
+        ui.label("Cole o CSV do banco (parcela; valor; saldo):");
+        ui.add(
+            egui::TextEdit::multiline(bank_schedule)
+                .desired_rows(6)
+                .hint_text("1;4.850,00;448.500,00"),
+        );
+
+        let rows = parse_bank_schedule(bank_schedule);
+        let loan = calculate_loan_schedule(house, strategy, 0);
+
+        let Some(report) = reconcile(&rows, &loan, house.house_monthly_interest) else {
+            ui.label("São necessárias ao menos duas parcelas com saldos diferentes.");
+            return;
+        };
+
+        Grid::new("bank_schedule_fit").show(ui, |ui| {
+            ui.label("Taxa cobrada sobre o saldo:");
+            ui.label(format!("{:.4}% a.m.", report.fitted_rate * 100.0));
+            ui.end_row();
+
+            ui.label("Seguro/TR sobre o saldo:");
+            ui.label(format!("{:.4}% a.m.", report.insurance_rate * 100.0));
+            ui.end_row();
+
+            ui.label("Taxas fixas mensais:");
+            ui.label(format_with_thousands_separator(report.monthly_fees));
+            ui.end_row();
+        });
+
+        ui.colored_label(
+            if report.divergent_months() > 0 {
+                Color32::RED
+            } else {
+                Color32::DARK_GREEN
+            },
+            format!(
+                "{} de {} meses divergem do nosso cálculo",
+                report.divergent_months(),
+                report.rows.len()
+            ),
+        );
+
+        egui::ScrollArea::vertical()
+            .id_salt("bank_schedule_rows")
+            .max_height(240.0)
+            .show(ui, |ui| {
+                Grid::new("bank_schedule_rows")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Mês");
+                        ui.strong("Banco");
+                        ui.strong("Nosso");
+                        ui.strong("Saldo Banco");
+                        ui.strong("Nosso Saldo");
+                        ui.end_row();
+
+                        for row in &report.rows {
+                            let color = if row.diverges {
+                                Color32::RED
+                            } else {
+                                ui.visuals().text_color()
+                            };
+
+                            ui.colored_label(color, row.month.to_string());
+                            ui.colored_label(
+                                color,
+                                format_with_thousands_separator(row.bank_installment),
+                            );
+                            ui.colored_label(
+                                color,
+                                format_with_thousands_separator(row.our_installment),
+                            );
+                            ui.colored_label(
+                                color,
+                                format_with_thousands_separator(row.bank_balance),
+                            );
+                            ui.colored_label(
+                                color,
+                                format_with_thousands_separator(row.our_balance),
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+    });
+}
+
+pub(crate) fn render_offers_params(ui: &mut Ui, offers: &mut Vec<Offer>) {
+    ui.collapsing("Ofertas dos Bancos", |ui| {
+        let mut to_remove = None;
+
+        Grid::new("offers").show(ui, |ui| {
+            ui.label("Banco");
+            ui.label("Juros");
+            ui.label("Parcelas");
+            ui.label("Tabela");
+            ui.label("Tarifas");
+            ui.label("Taxa Mensal");
+            ui.label("Seguro");
+            ui.end_row();
+
+            for (i, offer) in offers.iter_mut().enumerate() {
+                ui.push_id(i, |ui| {
+                    ui.add(egui::TextEdit::singleline(&mut offer.name).desired_width(80.0));
+                });
+                ui.add(
+                    egui::DragValue::new(&mut offer.monthly_interest)
+                        .range(0.0..=0.1)
+                        .speed(0.0001),
+                );
+                ui.add(egui::DragValue::new(&mut offer.months_to_pay).range(1..=420));
+                egui::ComboBox::from_id_salt(("offer_strategy", i))
+                    .selected_text(match offer.strategy {
+                        AmortizationStrategyType::Sac => "SAC",
+                        AmortizationStrategyType::Price => "PRICE",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut offer.strategy,
+                            AmortizationStrategyType::Sac,
+                            "SAC",
+                        );
+                        ui.selectable_value(
+                            &mut offer.strategy,
+                            AmortizationStrategyType::Price,
+                            "PRICE",
+                        );
+                    });
+                ui.add(
+                    egui::DragValue::new(&mut offer.fees.upfront)
+                        .range(0.0..=100_000.0)
+                        .prefix("R$ "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut offer.fees.monthly_fee)
+                        .range(0.0..=1_000.0)
+                        .prefix("R$ "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut offer.fees.monthly_insurance_rate)
+                        .range(0.0..=0.01)
+                        .speed(0.00001),
+                );
+
+                if ui.button("Remover").clicked() {
+                    to_remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(i) = to_remove {
+            offers.remove(i);
+        }
+
+        if ui.button("Adicionar").clicked() {
+            offers.push(Offer::default());
+        }
+    });
+}
+
+pub(crate) fn render_reverse_calculator(ui: &mut Ui, calculator: &mut ReverseCalculator) {
+    ui.collapsing("Calculadora Reversa", |ui| {
+        ui.add(
+            egui::Slider::new(&mut calculator.installment, 0.0..=100_000.0)
+                .text("Parcela Desejada"),
+        );
+        ui.add(
+            egui::Slider::new(&mut calculator.value, 0.0..=2_000_000.0).text("Valor Financiado"),
+        );
+        ui.add(egui::Slider::new(&mut calculator.monthly_interest, 0.0..=0.1).text("Juros Mensal"));
+        ui.add(
+            egui::Slider::new(&mut calculator.months_to_pay, 1..=420).text("Número de Parcelas"),
+        );
+        ui.horizontal(|ui| {
+            ui.selectable_value(
+                &mut calculator.strategy,
+                AmortizationStrategyType::Sac,
+                "Tabela SAC (1ª parcela)",
+            );
+            ui.selectable_value(
+                &mut calculator.strategy,
+                AmortizationStrategyType::Price,
+                "Tabela PRICE",
+            );
+        });
+
+        Grid::new("reverse_calculator").show(ui, |ui| {
+            ui.label("Prazo necessário:");
+            ui.label(
+                match solve_term(
+                    calculator.value,
+                    calculator.monthly_interest,
+                    calculator.installment,
+                    calculator.strategy,
+                    MAX_REVERSE_MONTHS,
+                ) {
+                    Some(n_months) => format!("{} meses", n_months),
+                    None => "Parcela não quita o valor".to_string(),
+                },
+            );
+            ui.end_row();
+
+            ui.label("Juros implícito:");
+            ui.label(
+                match solve_rate(
+                    calculator.value,
+                    calculator.months_to_pay,
+                    calculator.installment,
+                    calculator.strategy,
+                ) {
+                    Some(rate) => format!("{:.4}% a.m.", rate * 100.0),
+                    None => "Parcela fora do intervalo".to_string(),
+                },
+            );
+            ui.end_row();
+
+            ui.label("Valor financiável:");
+            ui.label(format_with_thousands_separator(solve_value(
+                calculator.monthly_interest,
+                calculator.months_to_pay,
+                calculator.installment,
+                calculator.strategy,
+            )));
+            ui.end_row();
+        });
+    });
 }
 
 pub(crate) fn render_simulation_params(
@@ -49,12 +1082,19 @@ pub(crate) fn render_simulation_params(
     simulation: &mut Simulation,
     strategy: &mut AmortizationStrategyType,
     plot_selection: &mut PlotSelection,
+    value_view: &mut ValueView,
 ) {
     ui.heading("Simulação");
     ui.add(
         egui::Slider::new(&mut simulation.months_to_forecast, 1..=720).text("Meses para Simular"),
     );
     ui.add(egui::Slider::new(&mut simulation.inflation, 0.0..=1.0).text("Inflação"));
+    ui.horizontal(|ui| {
+        ui.label("Início:");
+        ui.add(egui::DragValue::new(&mut simulation.start_month).range(1..=12));
+        ui.label("/");
+        ui.add(egui::DragValue::new(&mut simulation.start_year).range(1990..=2100));
+    });
 
     Grid::new("buttons").show(ui, |ui| {
         ui.label("Tabela:");
@@ -68,59 +1108,508 @@ pub(crate) fn render_simulation_params(
             "Dinheiro na Conta",
         );
         ui.selectable_value(plot_selection, PlotSelection::Payments, "Pagamentos");
+        ui.selectable_value(
+            plot_selection,
+            PlotSelection::StackedPayments,
+            "Parcelas por Financiamento",
+        );
+        ui.selectable_value(plot_selection, PlotSelection::NetWorth, "Patrimônio");
+        ui.selectable_value(plot_selection, PlotSelection::Runway, "Meses de Reserva");
+        ui.selectable_value(
+            plot_selection,
+            PlotSelection::CashFlows,
+            "Receitas e Despesas",
+        );
+        ui.selectable_value(
+            plot_selection,
+            PlotSelection::Expenses,
+            "Gastos por Categoria",
+        );
+        ui.selectable_value(plot_selection, PlotSelection::Offers, "Ofertas");
+        ui.end_row();
+        ui.label("Valores:");
+        ui.selectable_value(value_view, ValueView::Nominal, "Nominais");
+        ui.selectable_value(value_view, ValueView::Real, "Reais (hoje)");
+        ui.selectable_value(value_view, ValueView::Both, "Ambos");
         ui.end_row();
     });
 }
 
-pub(crate) fn render_kpis(ui: &mut Ui, sim_output: &SimulationOutput, simulation: &Simulation) {
+/// A version of the simulation results to be displayed, such as the results
+/// in nominal or in real values.
+pub(crate) struct ResultView<'a> {
+    pub(crate) label: &'a str,
+    pub(crate) sim_output: &'a SimulationOutput,
+    pub(crate) reserve_report: &'a ReserveReport,
+}
+
+pub(crate) fn render_kpis(ui: &mut Ui, views: &[ResultView], simulation: &Simulation) {
+    let Some(first_view) = views.first() else {
+        return;
+    };
+
+    if let Some(month) = first_view.sim_output.first_negative_month {
+        ui.colored_label(
+            Color32::RED,
+            format!(
+                "Atenção: saldo negativo a partir de {} (mês {})",
+                format_month_at(simulation, month - 1),
+                month
+            ),
+        );
+
+        for view in views {
+            ui.colored_label(
+                Color32::RED,
+                format!(
+                    "Juros pagos no cheque especial{}: {}",
+                    view_suffix(views, view),
+                    format_with_thousands_separator(view.sim_output.total_credit_interest())
+                ),
+            );
+        }
+    }
+
+    if let Some(first_violation) = first_view.reserve_report.violations.first() {
+        for view in views {
+            ui.colored_label(
+                Color32::from_rgb(230, 140, 0),
+                format!(
+                    "Abaixo da reserva mínima em {} meses (a partir do mês {}), pior déficit{}: {}",
+                    view.reserve_report.violations.len(),
+                    first_violation,
+                    view_suffix(views, view),
+                    format_with_thousands_separator(view.reserve_report.worst_shortfall)
+                ),
+            );
+        }
+        ui.collapsing("Meses abaixo da reserva", |ui| {
+            ui.label(
+                first_view
+                    .reserve_report
+                    .violations
+                    .iter()
+                    .map(|month| month.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        });
+    }
+
+    let retirements = &first_view.sim_output.retirements;
+    // Retirements are told apart by the name of their buyer when there are many.
+    let retirement_prefix = |retirement: &RetirementReport| {
+        if retirements.len() > 1 {
+            format!("{}: ", retirement.name)
+        } else {
+            String::new()
+        }
+    };
+
+    for retirement in retirements {
+        let prefix = retirement_prefix(retirement);
+
+        match (retirement.lasts_until_target, retirement.depleted_at) {
+            (Some(true), _) => {
+                ui.colored_label(
+                    Color32::DARK_GREEN,
+                    format!(
+                        "{}A reserva dura até {}, na idade alvo",
+                        prefix,
+                        format_month_at(simulation, retirement.target_month - 1)
+                    ),
+                );
+            }
+            (Some(false), Some(month)) => {
+                ui.colored_label(
+                    Color32::RED,
+                    format!(
+                        "{}A reserva acaba em {}, antes da idade alvo em {}",
+                        prefix,
+                        format_month_at(simulation, month - 1),
+                        format_month_at(simulation, retirement.target_month - 1)
+                    ),
+                );
+            }
+            _ => {
+                ui.label(format!(
+                    "{}A simulação termina antes da idade alvo, em {}",
+                    prefix,
+                    format_month_at(simulation, retirement.target_month - 1)
+                ));
+            }
+        }
+    }
+
+    let money_at = |view: &ResultView, month: Option<usize>| {
+        let series = &view.sim_output.time_series;
+
+        match month.map_or(series.last(), |month| series.get(month)) {
+            Some(v) => format_with_thousands_separator(*v),
+            None => "NaN".to_string(),
+        }
+    };
+
     Grid::new("grid").show(ui, |ui| {
+        if views.len() > 1 {
+            ui.label("");
+            for view in views {
+                ui.strong(view.label);
+            }
+            ui.end_row();
+        }
+
         ui.label("Dinheiro Inicial:");
-        ui.label(format!(
-            "{}",
-            format_with_thousands_separator(sim_output.time_series[0])
-        ));
+        for view in views {
+            ui.label(format_with_thousands_separator(
+                view.sim_output.time_series[0],
+            ));
+        }
         ui.end_row();
 
-        ui.label("Parcelas Mensais");
-        ui.label(format!(
-            "Primeira: {}",
-            format_with_thousands_separator(
-                sim_output.monthly_payments.first().copied().unwrap_or(0.0)
-            )
-        ));
-        ui.end_row();
-        ui.label("");
-        ui.label(format!(
-            "Última: {}",
-            format_with_thousands_separator(
-                sim_output.monthly_payments.last().copied().unwrap_or(0.0)
-            )
-        ));
-        ui.end_row();
+        let loans = &first_view.sim_output.loans;
 
-        ui.label("Parcels terminam em:");
-        ui.label(format!("{} meses", sim_output.ends_after));
-        ui.end_row();
+        for (j, loan) in loans.iter().enumerate() {
+            // Loans are told apart by the name of their house when there are many.
+            let prefix = if loans.len() > 1 {
+                format!("{} - ", loan.name)
+            } else {
+                String::new()
+            };
 
-        ui.label("Dinheiro depois de 1 ano:");
-        match sim_output.time_series.get(12 - 1) {
-            Some(v) => {
-                ui.label(format!("{}", format_with_thousands_separator(*v)));
+            if loan.purchased_after > 0 {
+                ui.label(format!("{}Compra (mês {}):", prefix, loan.purchased_after));
+                for view in views {
+                    ui.label(format!(
+                        "Entrada {}",
+                        format_with_thousands_separator(view.sim_output.loans[j].down_payment)
+                    ));
+                }
+                ui.end_row();
+            }
+
+            if loan.disbursement > 0.0 {
+                ui.label(format!(
+                    "{}Crédito liberado (mês {}):",
+                    prefix, loan.purchased_after
+                ));
+                for view in views {
+                    ui.label(format_with_thousands_separator(
+                        view.sim_output.loans[j].disbursement,
+                    ));
+                }
+                ui.end_row();
+            }
+
+            ui.label(format!("{}Parcelas Mensais", prefix));
+            for view in views {
+                ui.label(format!(
+                    "Primeira: {}",
+                    format_with_thousands_separator(
+                        view.sim_output.loans[j]
+                            .monthly_payments
+                            .first()
+                            .copied()
+                            .unwrap_or(0.0)
+                    )
+                ));
+            }
+            ui.end_row();
+            ui.label("");
+            for view in views {
+                ui.label(format!(
+                    "Última: {}",
+                    format_with_thousands_separator(
+                        view.sim_output.loans[j]
+                            .monthly_payments
+                            .last()
+                            .copied()
+                            .unwrap_or(0.0)
+                    )
+                ));
+            }
+            ui.end_row();
+
+            if loan.balloon_payments.iter().any(|balloon| *balloon > 0.0) {
+                ui.label(format!("{}Parcelas intermediárias:", prefix));
+                for view in views {
+                    ui.label(format_with_thousands_separator(
+                        view.sim_output.loans[j].balloon_payments.iter().sum(),
+                    ));
+                }
+                ui.end_row();
+            }
+
+            if loan.upfront_fees > 0.0 || loan.charges.iter().any(|charge| *charge > 0.0) {
+                ui.label(format!("{}Seguros e tarifas:", prefix));
+                for view in views {
+                    let loan = &view.sim_output.loans[j];
+
+                    ui.label(format_with_thousands_separator(
+                        loan.upfront_fees + loan.charges.iter().sum::<f64>(),
+                    ));
+                }
+                ui.end_row();
+            }
+
+            if let Some(month) = loan.pmi_drops_at {
+                ui.label(format!("{}PMI:", prefix));
+                ui.label(if month <= loan.starts_after + 1 {
+                    "Não cobrado".to_string()
+                } else {
+                    format!(
+                        "Até o mês {} ({})",
+                        month - 1,
+                        format_month_at(simulation, month - 2)
+                    )
+                });
+                ui.end_row();
+            }
+
+            if loan.escrow.iter().any(|escrow| *escrow > 0.0) {
+                ui.label(format!("{}Escrow (imposto e seguro):", prefix));
+                for view in views {
+                    ui.label(format_with_thousands_separator(
+                        view.sim_output.loans[j].escrow.iter().sum(),
+                    ));
+                }
+                ui.end_row();
+            }
+
+            ui.label(format!("{}Custo Efetivo Total (CET):", prefix));
+            for view in views {
+                ui.label(match view.sim_output.loans[j].effective_cost() {
+                    Some(rate) => format!("{:.2}% a.a.", rate * 100.0),
+                    None => "-".to_string(),
+                });
+            }
+            ui.end_row();
+
+            if let Some(last_reset) = loan.rate_resets.last() {
+                ui.label(format!(
+                    "{}Reajustes de taxa ({}):",
+                    prefix,
+                    loan.rate_resets.len()
+                ));
+                ui.label(format!(
+                    "Última: {:.3}% a.m. no mês {}",
+                    last_reset.monthly_interest * 100.0,
+                    last_reset.month
+                ));
+                ui.end_row();
+
+                ui.label("");
+                for view in views {
+                    let highest = view.sim_output.loans[j]
+                        .rate_resets
+                        .iter()
+                        .map(|reset| reset.first_payment)
+                        .fold(0.0, f64::max);
+
+                    ui.label(format!(
+                        "Maior parcela reajustada: {}",
+                        format_with_thousands_separator(highest)
+                    ));
+                }
+                ui.end_row();
+            }
+
+            ui.label(format!("{}Parcels terminam em:", prefix));
+            ui.label(if loan.ends_after > 0 {
+                format!(
+                    "{} meses (quitação em {})",
+                    loan.ends_after,
+                    format_month_at(simulation, loan.ends_after - 1)
+                )
+            } else {
+                format!("{} meses", loan.ends_after)
+            });
+            ui.end_row();
+
+            if let Some(sale) = &loan.sale {
+                ui.label(format!("{}Venda (mês {}):", prefix, sale.month));
+                for view in views {
+                    let sale = view.sim_output.loans[j].sale.as_ref().unwrap_or(sale);
+
+                    ui.label(format!(
+                        "Preço {}, comissão {}",
+                        format_with_thousands_separator(sale.sale_price),
+                        format_with_thousands_separator(sale.broker_commission)
+                    ));
+                }
+                ui.end_row();
+
+                ui.label("");
+                for view in views {
+                    let sale = view.sim_output.loans[j].sale.as_ref().unwrap_or(sale);
+
+                    ui.label(format!(
+                        "Quitação {}, GCAP {}",
+                        format_with_thousands_separator(sale.outstanding_balance),
+                        format_with_thousands_separator(sale.capital_gains_tax)
+                    ));
+                }
+                ui.end_row();
+
+                ui.label(format!("{}Valor líquido da venda:", prefix));
+                for view in views {
+                    let sale = view.sim_output.loans[j].sale.as_ref().unwrap_or(sale);
+
+                    ui.label(format_with_thousands_separator(sale.net_proceeds()));
+                }
+                ui.end_row();
+            }
+
+            if let Some(refinancing) = &loan.refinancing {
+                ui.label(format!(
+                    "{}Portabilidade (mês {}):",
+                    prefix, refinancing.month
+                ));
+                for view in views {
+                    let refinancing = view.sim_output.loans[j]
+                        .refinancing
+                        .as_ref()
+                        .unwrap_or(refinancing);
+
+                    ui.label(format!(
+                        "Parcela de {} para {}",
+                        format_with_thousands_separator(
+                            refinancing
+                                .original_payments
+                                .first()
+                                .copied()
+                                .unwrap_or(0.0)
+                        ),
+                        format_with_thousands_separator(refinancing.new_first_payment)
+                    ));
+                }
+                ui.end_row();
+
+                ui.label(format!("{}Economia com a portabilidade:", prefix));
+                for view in views {
+                    let refinancing = view.sim_output.loans[j]
+                        .refinancing
+                        .as_ref()
+                        .unwrap_or(refinancing);
+                    let net_savings = refinancing.net_savings();
+
+                    ui.colored_label(
+                        if net_savings >= 0.0 {
+                            Color32::DARK_GREEN
+                        } else {
+                            Color32::RED
+                        },
+                        format_with_thousands_separator(net_savings),
+                    );
+                }
+                ui.end_row();
             }
-            None => {
-                ui.label("NaN");
+
+            if loan.starts_after > loan.purchased_after {
+                ui.label(format!("{}Entrega das chaves:", prefix));
+                ui.label(format!("mês {}", loan.starts_after));
+                ui.end_row();
+
+                ui.label(format!("{}Pago à construtora:", prefix));
+                for view in views {
+                    ui.label(format_with_thousands_separator(
+                        view.sim_output.loans[j].construction_payments.iter().sum(),
+                    ));
+                }
+                ui.end_row();
+
+                ui.label(format!("{}Juros de obra:", prefix));
+                for view in views {
+                    ui.label(format_with_thousands_separator(
+                        view.sim_output.loans[j].construction_interest.iter().sum(),
+                    ));
+                }
+                ui.end_row();
             }
         }
+
+        ui.label("Patrimônio no fim da sim:");
+        for view in views {
+            ui.label(format_with_thousands_separator(
+                view.sim_output.net_worth.last().copied().unwrap_or(0.0),
+            ));
+        }
         ui.end_row();
 
-        ui.label("Dinheiro depois de 5 anos:");
-        match sim_output.time_series.get(5 * 12 - 1) {
-            Some(v) => {
-                ui.label(format!("{}", format_with_thousands_separator(*v)));
+        if let Some(yield_on_cost) = first_view.sim_output.rental_yield_on_cost {
+            ui.label("Aluguel líquido recebido:");
+            for view in views {
+                ui.label(format_with_thousands_separator(
+                    view.sim_output.rental_income.iter().sum(),
+                ));
             }
-            None => {
-                ui.label("NaN");
+            ui.end_row();
+
+            ui.label("Carnê-leão pago:");
+            for view in views {
+                ui.label(format_with_thousands_separator(
+                    view.sim_output.rental_taxes.iter().sum(),
+                ));
             }
+            ui.end_row();
+
+            ui.label("Yield on cost (1º ano):");
+            ui.label(format!("{:.2}% a.a.", yield_on_cost * 100.0));
+            ui.end_row();
+        }
+
+        for (j, retirement) in retirements.iter().enumerate() {
+            let prefix = retirement_prefix(retirement);
+            let sum_of = |view: &ResultView, series: fn(&RetirementReport) -> &[f64]| {
+                view.sim_output
+                    .retirements
+                    .get(j)
+                    .map_or(0.0, |retirement| series(retirement).iter().sum())
+            };
+
+            ui.label(format!(
+                "{}Reserva na aposentadoria ({}):",
+                prefix,
+                format_month_at(simulation, retirement.month)
+            ));
+            for view in views {
+                let savings = view.sim_output.retirements.get(j).map(|r| r.savings);
+                ui.label(format_with_thousands_separator(savings.unwrap_or(0.0)));
+            }
+            ui.end_row();
+
+            ui.label(format!("{prefix}Sacado da reserva na aposentadoria:"));
+            for view in views {
+                ui.label(format_with_thousands_separator(sum_of(view, |r| {
+                    &r.withdrawals
+                })));
+            }
+            ui.end_row();
+
+            if !retirement.allowed_withdrawals.is_empty() {
+                ui.label(format!("{prefix}Gastos cortados pelo limite de saque:"));
+                for view in views {
+                    ui.label(format_with_thousands_separator(sum_of(view, |r| {
+                        &r.expense_cuts
+                    })));
+                }
+                ui.end_row();
+
+                ui.label(format!("{prefix}Meses com saque acima do limite:"));
+                ui.label(retirement.months_over_rule().to_string());
+                ui.end_row();
+            }
+        }
+
+        ui.label("Dinheiro depois de 1 ano:");
+        for view in views {
+            ui.label(money_at(view, Some(12 - 1)));
+        }
+        ui.end_row();
+
+        ui.label("Dinheiro depois de 5 anos:");
+        for view in views {
+            ui.label(money_at(view, Some(5 * 12 - 1)));
         }
         ui.end_row();
 
@@ -128,52 +1617,507 @@ pub(crate) fn render_kpis(ui: &mut Ui, sim_output: &SimulationOutput, simulation
             "Dinheiro no fim da sim ({} meses)",
             simulation.months_to_forecast
         ));
-        match sim_output.time_series.last() {
-            Some(v) => {
-                ui.label(format!("{}", format_with_thousands_separator(*v)));
-            }
-            None => {
-                ui.label("NaN");
-            }
+        for view in views {
+            ui.label(money_at(view, None));
         }
 
         ui.end_row();
     });
 }
 
+/// Formats the calendar month of the given month of the simulation, counted
+/// from 0.
+fn format_month_at(simulation: &Simulation, month_index: i32) -> String {
+    let (year, month) = simulation.date_at(month_index);
+
+    plotting::format_month(year, month)
+}
+
+/// Renders the ranking of the offers, with the outputs of each offer in each
+/// of the views, labeled. The offers are ranked by the first view.
+pub(crate) fn render_offers(
+    ui: &mut Ui,
+    offers: &[Offer],
+    views: &[(&str, Vec<SimulationOutput>)],
+    simulation: &Simulation,
+    ranking: &mut OfferRanking,
+) {
+    let Some((_, outputs)) = views.first().filter(|_| !offers.is_empty()) else {
+        ui.label("Adicione ofertas em \"Ofertas dos Bancos\" para compará-las.");
+        return;
+    };
+
+    if views.len() > 1 {
+        ui.label(
+            views
+                .iter()
+                .map(|(label, _)| *label)
+                .collect::<Vec<_>>()
+                .join(" / "),
+        );
+    }
+
+    fn loan(output: &SimulationOutput) -> &LoanSchedule {
+        &output.loans[0]
+    }
+    let criterion = |output: &SimulationOutput| -> f64 {
+        match ranking {
+            OfferRanking::TotalCost => loan(output).total_paid(),
+            OfferRanking::EffectiveCost => loan(output).effective_cost().unwrap_or(f64::INFINITY),
+            OfferRanking::FirstInstallment => loan(output)
+                .monthly_payments
+                .first()
+                .copied()
+                .unwrap_or(0.0),
+            // Greater is better, so they are ranked by their negation.
+            OfferRanking::FinalNetWorth => -output.net_worth.last().copied().unwrap_or(0.0),
+            OfferRanking::MinimumMoney => -output
+                .time_series
+                .iter()
+                .copied()
+                .fold(f64::INFINITY, f64::min),
+        }
+    };
+
+    let mut order: Vec<usize> = (0..offers.len()).collect();
+    order.sort_by(|&a, &b| criterion(&outputs[a]).total_cmp(&criterion(&outputs[b])));
+
+    Grid::new("offers_ranking").striped(true).show(ui, |ui| {
+        ui.strong("#");
+        ui.strong("Banco");
+        ui.selectable_value(ranking, OfferRanking::TotalCost, "Custo Total");
+        ui.selectable_value(ranking, OfferRanking::EffectiveCost, "CET");
+        ui.selectable_value(ranking, OfferRanking::FirstInstallment, "1ª Parcela");
+        ui.selectable_value(ranking, OfferRanking::FinalNetWorth, "Patrimônio Final");
+        ui.selectable_value(ranking, OfferRanking::MinimumMoney, "Menor Saldo");
+        ui.end_row();
+
+        for (position, &i) in order.iter().enumerate() {
+            // The value of the offer in each view.
+            let cell = |ui: &mut Ui, format: &dyn Fn(&SimulationOutput) -> String| {
+                ui.label(
+                    views
+                        .iter()
+                        .map(|(_, outputs)| format(&outputs[i]))
+                        .collect::<Vec<_>>()
+                        .join(" / "),
+                );
+            };
+
+            ui.label((position + 1).to_string());
+            ui.label(&offers[i].name);
+            cell(ui, &|output| {
+                format_with_thousands_separator(loan(output).total_paid())
+            });
+            cell(ui, &|output| match loan(output).effective_cost() {
+                Some(rate) => format!("{:.2}% a.a.", rate * 100.0),
+                None => "-".to_string(),
+            });
+            cell(ui, &|output| {
+                format_with_thousands_separator(
+                    loan(output)
+                        .monthly_payments
+                        .first()
+                        .copied()
+                        .unwrap_or(0.0),
+                )
+            });
+            cell(ui, &|output| {
+                format_with_thousands_separator(output.net_worth.last().copied().unwrap_or(0.0))
+            });
+            cell(ui, &|output| {
+                format_with_thousands_separator(
+                    output
+                        .time_series
+                        .iter()
+                        .copied()
+                        .fold(f64::INFINITY, f64::min),
+                )
+            });
+            ui.end_row();
+        }
+    });
+
+    egui_plot::Plot::new("offers_plot")
+        .x_axis_formatter(plotting::format_x_axis(simulation))
+        .x_grid_spacer(egui_plot::log_grid_spacer(12))
+        .y_axis_formatter(plotting::format_y_axis)
+        .allow_zoom(false)
+        .allow_drag(false)
+        .allow_scroll(true)
+        .legend(Legend::default())
+        .show(ui, |plot_ui| {
+            for (j, (label, outputs)) in views.iter().enumerate() {
+                for &i in &order {
+                    let name = if views.len() > 1 {
+                        format!("{} ({})", offers[i].name, label)
+                    } else {
+                        offers[i].name.clone()
+                    };
+                    let line = Line::new(name, PlotPoints::from_ys_f64(&outputs[i].time_series));
+
+                    plot_ui.line(if j > 0 {
+                        line.style(LineStyle::dashed_loose())
+                    } else {
+                        line
+                    });
+                }
+            }
+        });
+}
+
+/// Gets the suffix that tells the views apart, if there are many of them.
+fn view_suffix(views: &[ResultView], view: &ResultView) -> String {
+    if views.len() > 1 {
+        format!(" ({})", view.label)
+    } else {
+        String::new()
+    }
+}
+
+/// Builds a line of the given view, dashing it if it is not the first one.
+fn view_line<'a>(
+    name: &str,
+    points: PlotPoints<'a>,
+    views: &[ResultView],
+    index: usize,
+) -> Line<'a> {
+    let line = Line::new(
+        format!("{}{}", name, view_suffix(views, &views[index])),
+        points,
+    );
+
+    if index > 0 {
+        line.style(LineStyle::dashed_loose())
+    } else {
+        line
+    }
+}
+
 pub(crate) fn render_plot(
     ui: &mut Ui,
-    sim_output: &SimulationOutput,
+    views: &[ResultView],
+    simulation: &Simulation,
     plot_selection: PlotSelection,
 ) {
     match plot_selection {
         PlotSelection::MoneyInAccount => {
-            let money_in_account = PlotPoints::from_ys_f64(&sim_output.time_series);
-
             egui_plot::Plot::new("plot")
+                .x_axis_formatter(plotting::format_x_axis(simulation))
+                .x_grid_spacer(egui_plot::log_grid_spacer(12))
                 .y_axis_formatter(plotting::format_y_axis)
                 .allow_zoom(false)
                 .allow_drag(false)
                 .allow_scroll(true)
                 .legend(Legend::default())
                 .show(ui, |plot_ui| {
-                    plot_ui.line(
-                        Line::new("Dinheiro na Conta", money_in_account).color(Color32::DARK_GREEN),
-                    )
+                    for (i, view) in views.iter().enumerate() {
+                        let money_in_account =
+                            PlotPoints::from_ys_f64(&view.sim_output.time_series);
+                        let reserve = PlotPoints::from_ys_f64(&view.reserve_report.reserve_line);
+
+                        plot_ui.line(
+                            view_line("Dinheiro na Conta", money_in_account, views, i)
+                                .color(Color32::DARK_GREEN),
+                        );
+                        plot_ui.line(
+                            view_line("Reserva Mínima", reserve, views, i).color(Color32::ORANGE),
+                        );
+
+                        for (name, money) in &view.sim_output.accounts {
+                            plot_ui.line(view_line(
+                                &format!("Conta de {name}"),
+                                PlotPoints::from_ys_f64(money),
+                                views,
+                                i,
+                            ));
+                        }
+                    }
                 });
         }
         PlotSelection::Payments => {
-            let payments = PlotPoints::from_ys_f64(&sim_output.monthly_payments);
+            egui_plot::Plot::new("plot")
+                .x_axis_formatter(plotting::format_x_axis(simulation))
+                .x_grid_spacer(egui_plot::log_grid_spacer(12))
+                .y_axis_formatter(plotting::format_y_axis)
+                .allow_zoom(false)
+                .allow_drag(false)
+                .allow_scroll(true)
+                .legend(Legend::default())
+                .show(ui, |plot_ui| {
+                    for (i, view) in views.iter().enumerate() {
+                        let loans = &view.sim_output.loans;
+
+                        for loan in loans {
+                            let name = |label: &str| {
+                                if loans.len() > 1 {
+                                    format!("{} ({})", label, loan.name)
+                                } else {
+                                    label.to_string()
+                                }
+                            };
+
+                            let payments: PlotPoints = loan
+                                .monthly_payments
+                                .iter()
+                                .enumerate()
+                                .map(|(j, payment)| {
+                                    [(j as i32 + loan.starts_after) as f64, *payment]
+                                })
+                                .collect();
+
+                            plot_ui.line(view_line(&name("Pagamentos"), payments, views, i));
+
+                            if loan.balloon_payments.iter().any(|balloon| *balloon > 0.0) {
+                                let balloons: PlotPoints = loan
+                                    .balloon_payments
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(j, balloon)| {
+                                        [(j as i32 + loan.starts_after) as f64, *balloon]
+                                    })
+                                    .collect();
+
+                                plot_ui.line(view_line(
+                                    &name("Intermediárias"),
+                                    balloons,
+                                    views,
+                                    i,
+                                ));
+                            }
 
+                            if loan.charges.iter().any(|charge| *charge > 0.0) {
+                                let charges: PlotPoints = loan
+                                    .charges
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(j, charge)| {
+                                        [(j as i32 + loan.starts_after) as f64, *charge]
+                                    })
+                                    .collect();
+
+                                plot_ui.line(view_line(
+                                    &name("Seguros e Tarifas"),
+                                    charges,
+                                    views,
+                                    i,
+                                ));
+                            }
+
+                            if loan.escrow.iter().any(|escrow| *escrow > 0.0) {
+                                let escrow: PlotPoints = loan
+                                    .escrow
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(j, escrow)| {
+                                        [(j as i32 + loan.starts_after) as f64, *escrow]
+                                    })
+                                    .collect();
+
+                                plot_ui.line(view_line(&name("Escrow"), escrow, views, i));
+                            }
+
+                            if let Some(refinancing) = &loan.refinancing {
+                                let original_payments: PlotPoints = refinancing
+                                    .original_payments
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(j, payment)| {
+                                        [(j as i32 + refinancing.month - 1) as f64, *payment]
+                                    })
+                                    .collect();
+
+                                plot_ui.line(
+                                    view_line(
+                                        &name("Sem Portabilidade"),
+                                        original_payments,
+                                        views,
+                                        i,
+                                    )
+                                    .color(Color32::GRAY),
+                                );
+                            }
+
+                            if loan.starts_after > loan.purchased_after {
+                                plot_ui.line(view_line(
+                                    &name("Construtora"),
+                                    PlotPoints::from_ys_f64(&loan.construction_payments),
+                                    views,
+                                    i,
+                                ));
+                                plot_ui.line(view_line(
+                                    &name("Juros de Obra"),
+                                    PlotPoints::from_ys_f64(&loan.construction_interest),
+                                    views,
+                                    i,
+                                ));
+                            }
+                        }
+                    }
+                });
+        }
+        PlotSelection::StackedPayments => {
             egui_plot::Plot::new("plot")
+                .x_axis_formatter(plotting::format_x_axis(simulation))
+                .x_grid_spacer(egui_plot::log_grid_spacer(12))
                 .y_axis_formatter(plotting::format_y_axis)
                 .allow_zoom(false)
                 .allow_drag(false)
                 .allow_scroll(true)
                 .legend(Legend::default())
                 .show(ui, |plot_ui| {
-                    plot_ui.line(Line::new("Pagamentos", payments))
+                    for (i, view) in views.iter().enumerate() {
+                        let sim_output = view.sim_output;
+                        let months = sim_output.monthly_outflows.len();
+
+                        let installments: Vec<Vec<f64>> = sim_output
+                            .loans
+                            .iter()
+                            .map(|loan| {
+                                (0..months)
+                                    .map(|j| {
+                                        loan.payment_at(j)
+                                            + loan.balloon_payment_at(j)
+                                            + loan.charges_at(j)
+                                            + loan.escrow_at(j)
+                                            + loan.construction_payment_at(j)
+                                    })
+                                    .collect()
+                            })
+                            .collect();
+
+                        // Drawn from the top down, so each area covers only its own loan.
+                        for (loan, stacked) in sim_output
+                            .loans
+                            .iter()
+                            .zip(plotting::stack_series(&installments))
+                            .rev()
+                        {
+                            let line =
+                                view_line(&loan.name, PlotPoints::from_ys_f64(&stacked), views, i);
+
+                            plot_ui.line(if i == 0 { line.fill(0.0) } else { line });
+                        }
+                    }
+                });
+        }
+        PlotSelection::NetWorth => {
+            egui_plot::Plot::new("plot")
+                .x_axis_formatter(plotting::format_x_axis(simulation))
+                .x_grid_spacer(egui_plot::log_grid_spacer(12))
+                .y_axis_formatter(plotting::format_y_axis)
+                .allow_zoom(false)
+                .allow_drag(false)
+                .allow_scroll(true)
+                .legend(Legend::default())
+                .show(ui, |plot_ui| {
+                    for (i, view) in views.iter().enumerate() {
+                        plot_ui.line(
+                            view_line(
+                                "Patrimônio Líquido",
+                                PlotPoints::from_ys_f64(&view.sim_output.net_worth),
+                                views,
+                                i,
+                            )
+                            .color(Color32::DARK_BLUE),
+                        );
+                        plot_ui.line(
+                            view_line(
+                                "Dinheiro na Conta",
+                                PlotPoints::from_ys_f64(&view.sim_output.time_series),
+                                views,
+                                i,
+                            )
+                            .color(Color32::DARK_GREEN),
+                        );
+                    }
+                });
+        }
+        PlotSelection::Runway => {
+            egui_plot::Plot::new("plot")
+                .x_axis_formatter(plotting::format_x_axis(simulation))
+                .x_grid_spacer(egui_plot::log_grid_spacer(12))
+                .allow_zoom(false)
+                .allow_drag(false)
+                .allow_scroll(true)
+                .legend(Legend::default())
+                .show(ui, |plot_ui| {
+                    for (i, view) in views.iter().enumerate() {
+                        // Months without any outflow have an infinite runway, which can't be drawn.
+                        let runway: PlotPoints = view
+                            .reserve_report
+                            .runway
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, months)| months.is_finite())
+                            .map(|(i, months)| [i as f64, *months])
+                            .collect();
+
+                        plot_ui.line(view_line("Meses de Reserva", runway, views, i));
+                    }
+                });
+        }
+        PlotSelection::CashFlows => {
+            egui_plot::Plot::new("plot")
+                .x_axis_formatter(plotting::format_x_axis(simulation))
+                .x_grid_spacer(egui_plot::log_grid_spacer(12))
+                .y_axis_formatter(plotting::format_y_axis)
+                .allow_zoom(false)
+                .allow_drag(false)
+                .allow_scroll(true)
+                .legend(Legend::default())
+                .show(ui, |plot_ui| {
+                    for (i, view) in views.iter().enumerate() {
+                        if view.sim_output.rental_yield_on_cost.is_some() {
+                            plot_ui.line(view_line(
+                                "Aluguel Líquido",
+                                PlotPoints::from_ys_f64(&view.sim_output.rental_income),
+                                views,
+                                i,
+                            ));
+                        }
+
+                        for (name, breakdown) in &view.sim_output.cash_flow_breakdown {
+                            plot_ui.line(view_line(
+                                name,
+                                PlotPoints::from_ys_f64(breakdown),
+                                views,
+                                i,
+                            ));
+                        }
+                    }
+                });
+        }
+        PlotSelection::Expenses => {
+            egui_plot::Plot::new("plot")
+                .x_axis_formatter(plotting::format_x_axis(simulation))
+                .x_grid_spacer(egui_plot::log_grid_spacer(12))
+                .y_axis_formatter(plotting::format_y_axis)
+                .allow_zoom(false)
+                .allow_drag(false)
+                .allow_scroll(true)
+                .legend(Legend::default())
+                .show(ui, |plot_ui| {
+                    for (i, view) in views.iter().enumerate() {
+                        plot_ui.line(view_line(
+                            "Gastos Gerais",
+                            PlotPoints::from_ys_f64(&view.sim_output.general_expenses),
+                            views,
+                            i,
+                        ));
+
+                        for (name, breakdown) in &view.sim_output.expense_breakdown {
+                            plot_ui.line(view_line(
+                                name,
+                                PlotPoints::from_ys_f64(breakdown),
+                                views,
+                                i,
+                            ));
+                        }
+                    }
                 });
         }
+        // The offers are simulated and rendered on their own by `render_offers`.
+        PlotSelection::Offers => {}
     }
 }